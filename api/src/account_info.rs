@@ -13,11 +13,46 @@ impl super::Client {
 pub struct AccountInfo {
     pub id: String,
     pub role: AccountInfoRole,
+    pub company: Option<AccountInfoCompany>,
+}
+
+impl AccountInfo {
+    /// The name the company does business as, or its legal name
+    pub fn company_name(&self) -> Option<&str> {
+        let company = self.company.as_ref()?;
+        Some(company.doing_business_as_name.as_deref().unwrap_or(&company.legal_name))
+    }
+
+    /// Full name of the person with this role
+    pub fn role_name(&self) -> Option<&str> {
+        Some(&self.role.user_cache.as_ref()?.name.full)
+    }
 }
 
 #[derive(Debug, Deserialize)]
 pub struct AccountInfoRole {
     pub company: Oid,
+    #[serde(rename = "workEmail")]
+    pub work_email: Option<String>,
+    pub user_cache: Option<AccountInfoUser>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AccountInfoUser {
+    pub name: AccountInfoName,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AccountInfoName {
+    pub full: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AccountInfoCompany {
+    #[serde(rename = "legalName")]
+    pub legal_name: String,
+    #[serde(rename = "doingBusinessAsName")]
+    pub doing_business_as_name: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -50,5 +85,8 @@ mod tests {
         let info = client.account_info().unwrap();
         assert_eq!(info.role.company.id, "some-company-id");
         assert_eq!(info.id, "my-role-id");
+        assert_eq!(info.company_name(), Some("My Employer, Inc."));
+        assert_eq!(info.role_name(), Some("John Doe"));
+        assert_eq!(info.role.work_email.as_deref(), Some("user@work.example"));
     }
 }
@@ -1,9 +1,16 @@
+use std::sync::Arc;
 use std::time::Duration;
 
-use crate::default_root;
+use rustls_pki_types::{pem::PemObject, CertificateDer};
 
-#[derive(Debug)]
+use crate::retry::{Request, RetryPolicy};
+use crate::{default_root, Error, Result};
+
+/// Cheap to clone, all clones share the same connection pool
+#[derive(Clone, Debug)]
 pub struct Client {
+    agent: ureq::Agent,
+    retry_policy: RetryPolicy,
     company: Option<String>,
     role: Option<String>,
     root: url::Url,
@@ -13,7 +20,7 @@ pub struct Client {
 /// Getters & instantiation
 impl Client {
     pub fn new(token: String) -> Self {
-        Self { company: None, role: None, root: default_root(), token }
+        ClientBuilder::new().build(token)
     }
 
     pub fn role(&self) -> Option<&String> {
@@ -25,53 +32,162 @@ impl Client {
     }
 
     pub fn with_company_and_role(&self, company: String, role: String) -> Self {
-        Self {
-            company: Some(company),
-            role: Some(role),
-            root: self.root.clone(),
-            token: self.token.clone(),
-        }
+        Self { company: Some(company), role: Some(role), ..self.clone() }
     }
 
     /// Used for testing mainly
     #[allow(dead_code)]
     pub(crate) fn with_root(&self, root: url::Url) -> Self {
+        Self { root, ..self.clone() }
+    }
+}
+
+/// Configures the connection of a `Client`
+#[derive(Clone, Debug)]
+pub struct ClientBuilder {
+    root: url::Url,
+    timeout_connect: Option<Duration>,
+    timeout_read: Option<Duration>,
+    timeout_write: Option<Duration>,
+    user_agent: Option<String>,
+    proxy_from_env: bool,
+    ca_certificates: Vec<CertificateDer<'static>>,
+    retry_policy: RetryPolicy,
+}
+
+impl Default for ClientBuilder {
+    fn default() -> Self {
         Self {
-            company: self.company.clone(),
-            role: self.role.clone(),
-            root,
-            token: self.token.clone(),
+            root: default_root(),
+            timeout_connect: None,
+            timeout_read: Some(Duration::from_secs(5)),
+            timeout_write: Some(Duration::from_secs(5)),
+            user_agent: None,
+            proxy_from_env: true,
+            ca_certificates: Vec::new(),
+            retry_policy: RetryPolicy::default(),
         }
     }
 }
 
-/// Methods for internal use
-impl Client {
-    fn agent(&self) -> ureq::Agent {
-        ureq::AgentBuilder::new()
-            .timeout_read(Duration::from_secs(5))
-            .timeout_write(Duration::from_secs(5))
-            .build()
+impl ClientBuilder {
+    pub fn new() -> Self {
+        Self::default()
     }
 
-    pub(super) fn get(&self, path: &str) -> ureq::Request {
-        let mut request = self
-            .agent()
-            .get(self.root.join(path).unwrap().as_str())
-            .set("Authorization", &format!("Bearer {}", self.token));
-        if let Some(company) = &self.company {
-            request = request.set("Company", company);
+    /// Root of the API, defaults to https://app.rippling.com/api/
+    pub fn root(self, root: url::Url) -> Self {
+        Self { root, ..self }
+    }
+
+    /// Defaults to no timeout besides the one of the operating system
+    pub fn timeout_connect(self, timeout: Duration) -> Self {
+        Self { timeout_connect: Some(timeout), ..self }
+    }
+
+    /// Defaults to 5 seconds
+    pub fn timeout_read(self, timeout: Duration) -> Self {
+        Self { timeout_read: Some(timeout), ..self }
+    }
+
+    /// Defaults to 5 seconds
+    pub fn timeout_write(self, timeout: Duration) -> Self {
+        Self { timeout_write: Some(timeout), ..self }
+    }
+
+    pub fn user_agent(self, user_agent: &str) -> Self {
+        Self { user_agent: Some(user_agent.to_owned()), ..self }
+    }
+
+    /// Use the proxy from ALL_PROXY, HTTPS_PROXY or HTTP_PROXY, enabled by default
+    pub fn proxy_from_env(self, enabled: bool) -> Self {
+        Self { proxy_from_env: enabled, ..self }
+    }
+
+    /// Trusts all certificates in `pem` in addition to the built-in roots,
+    /// for example the one of a TLS-intercepting proxy
+    pub fn add_ca_certificates(mut self, pem: &[u8]) -> Result<Self> {
+        let certificates = CertificateDer::pem_slice_iter(pem)
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|e| Error::Generic(format!("Invalid CA certificate: {e:?}")))?;
+        if certificates.is_empty() {
+            return Err(Error::Generic("No CA certificate found".into()));
         }
-        if let Some(role) = &self.role {
-            request = request.set("Role", role);
+        self.ca_certificates.extend(certificates);
+        Ok(self)
+    }
+
+    /// Defaults to `RetryPolicy::default()`
+    pub fn retry_policy(self, retry_policy: RetryPolicy) -> Self {
+        Self { retry_policy, ..self }
+    }
+
+    pub fn build(self, token: String) -> Client {
+        Client {
+            agent: self.agent(),
+            retry_policy: self.retry_policy,
+            company: None,
+            role: None,
+            root: self.root,
+            token,
+        }
+    }
+
+    /// Keeps connections alive, so consecutive requests skip the TCP and TLS handshakes
+    fn agent(&self) -> ureq::Agent {
+        let mut builder = ureq::AgentBuilder::new().try_proxy_from_env(self.proxy_from_env);
+        if let Some(timeout) = self.timeout_connect {
+            builder = builder.timeout_connect(timeout);
+        }
+        if let Some(timeout) = self.timeout_read {
+            builder = builder.timeout_read(timeout);
+        }
+        if let Some(timeout) = self.timeout_write {
+            builder = builder.timeout_write(timeout);
+        }
+        if let Some(user_agent) = &self.user_agent {
+            builder = builder.user_agent(user_agent);
+        }
+        if !self.ca_certificates.is_empty() {
+            builder = builder.tls_config(Arc::new(self.tls_config()));
         }
-        request
+        builder.build()
     }
 
-    pub(super) fn post(&self, path: &str) -> ureq::Request {
+    /// Same as the default of ureq, with the additional certificates
+    fn tls_config(&self) -> rustls::ClientConfig {
+        let mut roots = rustls::RootCertStore { roots: webpki_roots::TLS_SERVER_ROOTS.to_vec() };
+        roots.add_parsable_certificates(self.ca_certificates.iter().cloned());
+        rustls::ClientConfig::builder_with_provider(rustls::crypto::ring::default_provider().into())
+            .with_protocol_versions(&[&rustls::version::TLS12, &rustls::version::TLS13])
+            .unwrap()
+            .with_root_certificates(roots)
+            .with_no_client_auth()
+    }
+}
+
+/// Methods for internal use
+impl Client {
+    pub(super) fn get(&self, path: &str) -> Request {
+        self.request("GET", path)
+    }
+
+    pub(super) fn post(&self, path: &str) -> Request {
+        self.request("POST", path)
+    }
+
+    pub(super) fn patch(&self, path: &str) -> Request {
+        self.request("PATCH", path)
+    }
+
+    pub(super) fn delete(&self, path: &str) -> Request {
+        self.request("DELETE", path)
+    }
+
+    fn request(&self, method: &str, path: &str) -> Request {
         let mut request = self
-            .agent()
-            .post(self.root.join(path).unwrap().as_str())
+            .agent
+            .request(method, self.root.join(path).unwrap().as_str())
             .set("Authorization", &format!("Bearer {}", self.token));
         if let Some(company) = &self.company {
             request = request.set("Company", company);
@@ -79,6 +195,58 @@ impl Client {
         if let Some(role) = &self.role {
             request = request.set("Role", role);
         }
-        request
+        Request::new(request, self.retry_policy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use utilities::mocking;
+
+    use super::*;
+
+    const CA_CERTIFICATE: &str = "\
+-----BEGIN CERTIFICATE-----
+MIIBhTCCASugAwIBAgIUG8vpaxm++2wvDffWFvZUq1vo0EQwCgYIKoZIzj0EAwIw
+GDEWMBQGA1UEAwwNVGVzdCBQcm94eSBDQTAeFw0yNjEwMTcwMzQzMTJaFw0zNjEw
+MTQwMzQzMTJaMBgxFjAUBgNVBAMMDVRlc3QgUHJveHkgQ0EwWTATBgcqhkjOPQIB
+BggqhkjOPQMBBwNCAAReIdY1DvOAtbD/XsmvozeqsLCevGiP833ceOVs/AYIqrv3
+C+wlAk9Ny+lzCl0d4g9l1gkH/pNNlNCabFCkAK5eo1MwUTAdBgNVHQ4EFgQU6y8H
+tCer7fXFzvoTPfUcXSK2ETswHwYDVR0jBBgwFoAU6y8HtCer7fXFzvoTPfUcXSK2
+ETswDwYDVR0TAQH/BAUwAwEB/zAKBggqhkjOPQQDAgNIADBFAiEA20gkAh4M47A1
+YoCXL5p+Pk5/2ek0kOpbrN21Q9+mdmECIHy4y1Uo3eXQgzEddG4r6C/gfKP4Lu6E
+oZ+5XVH0FLG6
+-----END CERTIFICATE-----
+";
+
+    #[test]
+    fn it_builds_a_client_with_root_and_user_agent() {
+        let mut server = mocking::FakeRippling::new();
+        let m = server
+            .with_fixture("GET", "/auth_ext/get_account_info/", "account_info")
+            .match_header("user-agent", "rippling-cli-test")
+            .create();
+
+        let client = ClientBuilder::new()
+            .root(url::Url::parse(&server.url()).unwrap())
+            .timeout_connect(Duration::from_secs(1))
+            .user_agent("rippling-cli-test")
+            .proxy_from_env(false)
+            .add_ca_certificates(CA_CERTIFICATE.as_bytes())
+            .unwrap()
+            .build("access-token".into());
+        client.account_info().unwrap();
+        m.assert();
+    }
+
+    #[test]
+    fn it_rejects_files_without_certificates() {
+        assert!(ClientBuilder::new().add_ca_certificates(b"not a certificate").is_err());
+    }
+
+    #[test]
+    fn it_can_be_shared_between_threads() {
+        fn assert_shareable<T: Clone + Send + Sync>() {}
+        assert_shareable::<Client>();
     }
 }
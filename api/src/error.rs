@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 #[derive(Debug)]
 pub enum Error {
     ApiError {
@@ -5,7 +7,16 @@ pub enum Error {
         description: Option<String>,
         json: Option<serde_json::Value>,
     },
+    /// The access token is missing, invalid or expired
+    Unauthorized,
+    /// The access token is valid, but may not do this
+    Forbidden(Option<String>),
     Generic(String),
+    /// The server is overloaded or rate limits and asks to wait this long, more than the `RetryPolicy` allows
+    RetryAfter {
+        status: u16,
+        wait: Duration,
+    },
     UnexpectedPayload,
     UnhandledStatus(u16),
 }
@@ -17,6 +28,16 @@ impl std::fmt::Display for Error {
                 Some(string) => write!(f, "{string}"),
                 None => write!(f, "Unexpected response status {status}"),
             },
+            Self::Unauthorized => write!(f, "The access token is invalid or expired"),
+            Self::Forbidden(description) => match description {
+                Some(string) => write!(f, "Access denied: {string}"),
+                None => write!(f, "Access denied"),
+            },
+            Self::RetryAfter { status, wait } => write!(
+                f,
+                "The server is busy (status {status}), retry in {} seconds",
+                wait.as_secs()
+            ),
             Self::UnexpectedPayload => write!(f, "Unexpected account info response"),
             Self::UnhandledStatus(status) => write!(f, "Unexpected response status {status}"),
             Self::Generic(err) => write!(f, "{err}"),
@@ -44,6 +65,38 @@ impl From<url::ParseError> for Error {
 
 impl From<ureq::Error> for Error {
     fn from(value: ureq::Error) -> Self {
+        if let ureq::Error::Status(status @ (429 | 503), _) = &value {
+            if let Some(wait) = crate::retry::retry_after(&value) {
+                return Self::RetryAfter { status: *status, wait };
+            }
+        }
+        Self::from_response(value).detect_auth_failure()
+    }
+}
+
+impl Error {
+    /// Rippling answers some requests with an invalid token with 403 instead of 401
+    fn detect_auth_failure(self) -> Self {
+        match self {
+            Self::ApiError { status: 401, .. } | Self::UnhandledStatus(401) => Self::Unauthorized,
+            Self::ApiError { status: 403, description, json } => {
+                let code = json.as_ref().and_then(|j| j.get("code")).and_then(|c| c.as_str());
+                let about_token = description.as_deref().is_some_and(|d| {
+                    let d = d.to_lowercase();
+                    d.contains("token") || d.contains("credentials")
+                });
+                if code == Some("token_not_valid") || about_token {
+                    Self::Unauthorized
+                } else {
+                    Self::Forbidden(description)
+                }
+            }
+            Self::UnhandledStatus(403) => Self::Forbidden(None),
+            other => other,
+        }
+    }
+
+    fn from_response(value: ureq::Error) -> Self {
         let desc = format!("{value}");
         match value.into_response() {
             Some(res) => match res.header("Content-Type") {
@@ -75,6 +128,12 @@ impl From<ureq::Error> for Error {
     }
 }
 
+impl From<Box<ureq::Error>> for Error {
+    fn from(value: Box<ureq::Error>) -> Self {
+        Error::from(*value)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use serde_json::json;
@@ -96,7 +155,7 @@ mod tests {
         match req {
             Ok(ok) => {
                 dbg!(ok);
-                assert!(false);
+                unreachable!();
             }
             Err(error) => {
                 let error: crate::Error = error.into();
@@ -105,12 +164,56 @@ mod tests {
                         assert_eq!(status, 400);
                         assert_eq!(description, Some("Oops!".into()));
                     }
-                    _ => assert!(false),
+                    _ => unreachable!(),
                 }
             }
         }
     }
 
+    fn error_for(status: usize, body: serde_json::Value) -> Error {
+        let mut server = mocking::FakeRippling::new();
+        let _m = server
+            .mock("GET", mocking::Matcher::Any)
+            .with_status(status)
+            .with_header("content-type", "application/json")
+            .with_body(body.to_string())
+            .create();
+        ureq::get(&server.url()).call().unwrap_err().into()
+    }
+
+    #[test]
+    fn it_detects_invalid_tokens() {
+        assert!(matches!(
+            error_for(401, json!({"detail": "Unauthorized"})),
+            Error::Unauthorized
+        ));
+        assert!(matches!(
+            error_for(
+                403,
+                json!({"detail": "Given token not valid for any token type", "code": "token_not_valid"})
+            ),
+            Error::Unauthorized
+        ));
+        assert!(matches!(
+            error_for(403, json!({"detail": "Authentication credentials were not provided."})),
+            Error::Unauthorized
+        ));
+    }
+
+    #[test]
+    fn it_detects_missing_permissions() {
+        match error_for(
+            403,
+            json!({"detail": "You do not have permission to perform this action."}),
+        ) {
+            Error::Forbidden(description) => assert_eq!(
+                description,
+                Some("You do not have permission to perform this action.".into())
+            ),
+            _ => unreachable!(),
+        }
+    }
+
     #[test]
     fn it_can_parse_detail_errors() {
         let mut server = mocking::FakeRippling::new();
@@ -125,7 +228,7 @@ mod tests {
         match req {
             Ok(ok) => {
                 dbg!(ok);
-                assert!(false);
+                unreachable!();
             }
             Err(error) => {
                 let error: crate::Error = error.into();
@@ -134,7 +237,7 @@ mod tests {
                         assert_eq!(status, 404);
                         assert_eq!(description, Some("Not found".into()));
                     }
-                    _ => assert!(false),
+                    _ => unreachable!(),
                 }
             }
         }
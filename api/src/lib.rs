@@ -3,10 +3,14 @@ pub mod break_policy;
 pub mod client;
 mod error;
 pub mod pto;
+mod retry;
 pub mod time_entries;
 
-pub use client::Client;
+use serde::{Deserialize, Deserializer};
+
+pub use client::{Client, ClientBuilder};
 pub use error::Error;
+pub use retry::RetryPolicy;
 
 const DEFAULT_HOST: &str = "https://app.rippling.com";
 const API_ROOT: &str = "/api/";
@@ -20,3 +24,11 @@ fn default_host() -> url::Url {
 fn default_root() -> url::Url {
     default_host().join(API_ROOT).unwrap()
 }
+
+fn f32_from_str<'de, D>(deserializer: D) -> std::result::Result<f32, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer).unwrap();
+    Ok(s.parse::<f32>().unwrap())
+}
@@ -1,5 +1,7 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize, Serializer};
 use serde_json::json;
+use time::format_description::FormatItem;
+use time::macros::format_description;
 use time::Date;
 
 use super::Result;
@@ -13,9 +15,13 @@ impl crate::Client {
         Ok(holidays)
     }
 
-    pub fn leave_requests(&self) -> Result<Vec<LeaveRequest>> {
+    /// Leave requests of the current role, optionally only those with the given status (for example `APPROVED`)
+    pub fn leave_requests(&self, status: Option<&str>) -> Result<Vec<LeaveRequest>> {
         let role = self.role().unwrap();
-        let query: Vec<(&str, &str)> = vec![("role", role), ("status", "APPROVED")];
+        let mut query: Vec<(&str, &str)> = vec![("role", role)];
+        if let Some(status) = status {
+            query.push(("status", status));
+        }
         let requests: Vec<LeaveRequest> = self
             .get("pto/api/leave_requests/")
             .query_pairs(query)
@@ -23,6 +29,50 @@ impl crate::Client {
             .into_json()?;
         Ok(requests)
     }
+
+    /// Leave types configured for the company
+    pub fn leave_types(&self) -> Result<Vec<LeaveType>> {
+        let types: Vec<LeaveType> = self.get("pto/api/company_leave_types/").call()?.into_json()?;
+        Ok(types)
+    }
+
+    /// Leave policies the current role is eligible for
+    pub fn leave_policies(&self) -> Result<Vec<LeavePolicy>> {
+        let query: Vec<(&str, &str)> = vec![("role", self.role().unwrap())];
+        let policies: Vec<LeavePolicy> = self
+            .get("pto/api/leave_policies/")
+            .query_pairs(query)
+            .call()?
+            .into_json()?;
+        Ok(policies)
+    }
+
+    /// Balances of the current role, one per leave policy
+    pub fn leave_balances(&self) -> Result<Vec<LeaveBalance>> {
+        let query: Vec<(&str, &str)> = vec![("role", self.role().unwrap())];
+        let balances: Vec<LeaveBalance> = self
+            .get("pto/api/leave_balances/")
+            .query_pairs(query)
+            .call()?
+            .into_json()?;
+        Ok(balances)
+    }
+
+    /// Cancels a pending or withdraws an approved leave request
+    pub fn cancel_leave_request(&self, id: &str) -> Result<LeaveRequest> {
+        let request: LeaveRequest = self
+            .post(&format!("pto/api/leave_requests/{id}/cancel/"))
+            .send_json(json!({}))?
+            .into_json()?;
+        Ok(request)
+    }
+
+    pub fn create_leave_request(&self, request: &NewLeaveRequest) -> Result<LeaveRequest> {
+        let mut body = json!(request);
+        body["role"] = json!(self.role().unwrap());
+        let request: LeaveRequest = self.post("pto/api/leave_requests/").send_json(body)?.into_json()?;
+        Ok(request)
+    }
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -46,15 +96,162 @@ pub struct Holiday {
 
 #[derive(Clone, Debug, Deserialize)]
 pub struct LeaveRequest {
+    pub id: String,
     #[serde(rename = "isDeleted")]
     pub is_deleted: Option<bool>,
     #[serde(rename = "startDate")]
     pub start_date: Date,
     #[serde(rename = "endDate")]
     pub end_date: Date,
+    #[serde(rename = "startDateHalfDay")]
+    pub start_date_half_day: bool,
+    #[serde(rename = "endDateHalfDay")]
+    pub end_date_half_day: bool,
+    #[serde(rename = "reasonForLeave")]
+    pub reason: Option<String>,
     pub status: String,
+    #[serde(rename = "leavePolicy")]
+    pub leave_policy_id: String,
+    #[serde(rename = "leaveTypeName")]
+    pub leave_type_name: String,
+}
+
+/// Unit in which a leave policy accrues and counts leave
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AccrualUnit {
+    Days,
+    Hours,
+}
+
+impl From<bool> for AccrualUnit {
+    fn from(accrue_in_days: bool) -> Self {
+        if accrue_in_days {
+            Self::Days
+        } else {
+            Self::Hours
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct LeaveType {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    #[serde(rename = "isPaid")]
+    pub paid: bool,
+    #[serde(rename = "isDeleted")]
+    pub deleted: bool,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct LeavePolicy {
+    pub id: String,
+    #[serde(rename = "displayName")]
+    pub name: String,
+    #[serde(rename = "companyLeaveType")]
+    pub leave_type_id: String,
+    #[serde(rename = "accrueInDays")]
+    accrue_in_days: bool,
+    #[serde(rename = "isUnlimited")]
+    pub unlimited: bool,
+    #[serde(rename = "isDeleted")]
+    pub deleted: bool,
+}
+
+impl LeavePolicy {
+    pub fn unit(&self) -> AccrualUnit {
+        self.accrue_in_days.into()
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct LeaveBalance {
+    #[serde(rename = "leavePolicy")]
+    pub leave_policy_id: String,
+    #[serde(rename = "policyDisplayName")]
+    pub policy_name: String,
     #[serde(rename = "leaveTypeName")]
     pub leave_type_name: String,
+    #[serde(rename = "policyAccrueInDays")]
+    accrue_in_days: bool,
+    #[serde(rename = "isUnlimited")]
+    pub unlimited: bool,
+    #[serde(deserialize_with = "crate::f32_from_str")]
+    pub accrued: f32,
+    #[serde(deserialize_with = "crate::f32_from_str")]
+    pub used: f32,
+    #[serde(deserialize_with = "crate::f32_from_str")]
+    pub pending: f32,
+    #[serde(deserialize_with = "crate::f32_from_str")]
+    pub available: f32,
+}
+
+impl LeaveBalance {
+    pub fn unit(&self) -> AccrualUnit {
+        self.accrue_in_days.into()
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct NewLeaveRequest {
+    #[serde(rename = "leavePolicy")]
+    pub leave_policy_id: String,
+    #[serde(rename = "startDate")]
+    pub start_date: Date,
+    #[serde(rename = "endDate")]
+    pub end_date: Date,
+    #[serde(rename = "startDateHalfDay")]
+    pub start_date_half_day: bool,
+    #[serde(rename = "endDateHalfDay")]
+    pub end_date_half_day: bool,
+    /// Hours taken on the first day, instead of the full day
+    #[serde(rename = "startDateCustomHours", serialize_with = "hours_to_str")]
+    pub start_date_custom_hours: Option<f32>,
+    /// Hours taken on the last day, instead of the full day
+    #[serde(rename = "endDateCustomHours", serialize_with = "hours_to_str")]
+    pub end_date_custom_hours: Option<f32>,
+    #[serde(rename = "reasonForLeave")]
+    pub reason: Option<String>,
+}
+
+impl NewLeaveRequest {
+    pub fn new(leave_policy_id: String, start_date: Date, end_date: Date) -> Self {
+        Self {
+            leave_policy_id,
+            start_date,
+            end_date,
+            start_date_half_day: false,
+            end_date_half_day: false,
+            start_date_custom_hours: None,
+            end_date_custom_hours: None,
+            reason: None,
+        }
+    }
+}
+
+const DATE_FMT: &[FormatItem] = format_description!("[weekday repr:short] [day] [month repr:short] [year]");
+
+impl std::fmt::Display for LeaveRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {}",
+            self.leave_type_name,
+            self.start_date.format(DATE_FMT).unwrap()
+        )?;
+        if self.end_date != self.start_date {
+            write!(f, " - {}", self.end_date.format(DATE_FMT).unwrap())?;
+        }
+        write!(f, " ({})", self.status)
+    }
+}
+
+fn hours_to_str<S>(hours: &Option<f32>, serializer: S) -> std::result::Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str(&format!("{:.2}", hours.unwrap_or(0.0)))
 }
 
 #[cfg(test)]
@@ -84,12 +281,106 @@ mod tests {
                 "leave_requests",
             )
             .create();
-        let data = client.leave_requests().unwrap();
+        let data = client.leave_requests(Some("APPROVED")).unwrap();
         assert_eq!(data.len(), 2);
         let days: Vec<Date> = data.into_iter().map(|h| h.start_date).collect();
         assert_eq!(days, vec![date![2022 - 06 - 09], date![2022 - 05 - 23]]);
     }
 
+    #[test]
+    fn it_can_fetch_leave_requests_of_any_status() {
+        let (mut server, client) = setup();
+        let m = server
+            .with_fixture("GET", "/pto/api/leave_requests/?role=some-role-id", "leave_requests")
+            .create();
+        let data = client.leave_requests(None).unwrap();
+        m.assert();
+        assert_eq!(
+            data.first().unwrap().to_string(),
+            "Vacation Thu 09 Jun 2022 - Fri 10 Jun 2022 (APPROVED)"
+        );
+    }
+
+    #[test]
+    fn it_can_fetch_leave_types() {
+        let (mut server, client) = setup();
+        let _m = server
+            .with_fixture("GET", "/pto/api/company_leave_types/", "leave_types")
+            .create();
+        let data = client.leave_types().unwrap();
+        let paid: Vec<bool> = data.iter().map(|t| t.paid).collect();
+        assert_eq!(paid, vec![true, true, false]);
+        assert_eq!(data.first().unwrap().name, "Vacation");
+    }
+
+    #[test]
+    fn it_can_fetch_leave_policies() {
+        let (mut server, client) = setup();
+        let _m = server
+            .with_fixture("GET", "/pto/api/leave_policies/?role=some-role-id", "leave_policies")
+            .create();
+        let data = client.leave_policies().unwrap();
+        let policy = data.first().unwrap();
+        assert_eq!(policy.leave_type_id, "company-vacation-leave-type-id");
+        assert_eq!(policy.unit(), AccrualUnit::Days);
+        assert!(data.last().unwrap().unlimited);
+    }
+
+    #[test]
+    fn it_can_fetch_leave_balances() {
+        let (mut server, client) = setup();
+        let _m = server
+            .with_fixture("GET", "/pto/api/leave_balances/?role=some-role-id", "leave_balances")
+            .create();
+        let data = client.leave_balances().unwrap();
+        assert_eq!(data.len(), 2);
+        let vacation = data.first().unwrap();
+        assert_eq!(vacation.unit(), AccrualUnit::Days);
+        assert_eq!(vacation.available, 9.0);
+        assert_eq!(vacation.pending, 6.5);
+        assert_eq!(data.last().unwrap().unit(), AccrualUnit::Hours);
+    }
+
+    #[test]
+    fn it_can_cancel_leave_requests() {
+        let (mut server, client) = setup();
+        let m = server
+            .with_fixture("POST", "/pto/api/leave_requests/pto-request-3/cancel/", "leave_request")
+            .create();
+        client.cancel_leave_request("pto-request-3").unwrap();
+        m.assert();
+    }
+
+    #[test]
+    fn it_can_create_leave_requests() {
+        let (mut server, client) = setup();
+        let mut request = NewLeaveRequest::new(
+            "vacation-leave-policy-id".into(),
+            date![2026 - 12 - 22],
+            date![2026 - 12 - 31],
+        );
+        request.end_date_half_day = true;
+        request.reason = Some("Christmas".into());
+        let m = server
+            .with_fixture("POST", "/pto/api/leave_requests/", "leave_request")
+            .match_body(mocking::Matcher::Json(json!({
+                "role": "some-role-id",
+                "leavePolicy": "vacation-leave-policy-id",
+                "startDate": "2026-12-22",
+                "endDate": "2026-12-31",
+                "startDateHalfDay": false,
+                "endDateHalfDay": true,
+                "startDateCustomHours": "0.00",
+                "endDateCustomHours": "0.00",
+                "reasonForLeave": "Christmas"
+            })))
+            .create();
+        let created = client.create_leave_request(&request).unwrap();
+        m.assert();
+        assert_eq!(created.status, "PENDING");
+        assert!(created.end_date_half_day);
+    }
+
     #[test]
     fn it_can_fetch_holiday_calendar() {
         let (mut server, client) = setup();
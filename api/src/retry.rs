@@ -0,0 +1,227 @@
+use std::{thread, time::Duration};
+
+use serde::{de::DeserializeOwned, Serialize};
+use time::{format_description::well_known::Rfc2822, OffsetDateTime};
+
+use crate::Result;
+
+/// How often and how long to wait before sending a request again after a transient failure
+///
+/// GET requests are retried on connection errors and on 429, 502, 503 and 504. Other methods
+/// only when the request was certainly not processed: the connection could not be established
+/// or the server answered 429.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    /// Wait before the first retry, doubled for each further one
+    pub base_delay: Duration,
+    /// Upper limit for a single wait, a request is not retried when the server asks to wait longer
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_millis(250),
+            max_delay: Duration::from_secs(8),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Never retry
+    pub fn none() -> Self {
+        Self { max_retries: 0, ..Self::default() }
+    }
+
+    /// Wait before retry number `attempt` (starting at 0), with jitter unless the server asked for a delay
+    ///
+    /// `None` when the server asked to wait longer than `max_delay`.
+    fn delay(&self, attempt: u32, retry_after: Option<Duration>) -> Option<Duration> {
+        if let Some(delay) = retry_after {
+            return Some(delay).filter(|delay| *delay <= self.max_delay);
+        }
+        let delay = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_delay);
+        // Between half and the full delay, so clients failing together do not retry together
+        Some(delay / 2 + delay.mul_f64(fastrand::f64() / 2.0))
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Failure {
+    /// The server did certainly not process the request
+    NotSent,
+    /// The request might have been processed
+    Transient,
+    Permanent,
+}
+
+fn classify(error: &ureq::Error) -> Failure {
+    match error {
+        ureq::Error::Transport(transport) => match transport.kind() {
+            ureq::ErrorKind::Dns | ureq::ErrorKind::ConnectionFailed | ureq::ErrorKind::ProxyConnect => {
+                Failure::NotSent
+            }
+            ureq::ErrorKind::Io => Failure::Transient,
+            _ => Failure::Permanent,
+        },
+        ureq::Error::Status(429, _) => Failure::NotSent,
+        ureq::Error::Status(502..=504, _) => Failure::Transient,
+        ureq::Error::Status(_, _) => Failure::Permanent,
+    }
+}
+
+/// The wait the server asked for in the `Retry-After` header of an error response
+pub(crate) fn retry_after(error: &ureq::Error) -> Option<Duration> {
+    match error {
+        ureq::Error::Status(_, response) => {
+            parse_retry_after(response.header("Retry-After")?, OffsetDateTime::now_utc())
+        }
+        ureq::Error::Transport(_) => None,
+    }
+}
+
+/// `Retry-After` is either a number of seconds or an HTTP date, a date in the past means no wait
+fn parse_retry_after(value: &str, now: OffsetDateTime) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let at = OffsetDateTime::parse(value, &Rfc2822).ok()?;
+    Some(Duration::try_from(at - now).unwrap_or(Duration::ZERO))
+}
+
+/// `ureq::Error` is large, so it is boxed until converted into an `Error`
+pub(crate) type SendResult = std::result::Result<ureq::Response, Box<ureq::Error>>;
+
+/// A request which is sent again according to the `RetryPolicy` of the client
+#[derive(Clone)]
+pub(crate) struct Request {
+    inner: ureq::Request,
+    policy: RetryPolicy,
+}
+
+impl Request {
+    pub(crate) fn new(inner: ureq::Request, policy: RetryPolicy) -> Self {
+        Self { inner, policy }
+    }
+
+    pub(crate) fn query_pairs<'a, P>(self, pairs: P) -> Self
+    where
+        P: IntoIterator<Item = (&'a str, &'a str)>,
+    {
+        Self { inner: self.inner.query_pairs(pairs), ..self }
+    }
+
+    pub(crate) fn call(self) -> SendResult {
+        self.send(|request| request.call().map_err(Box::new))
+    }
+
+    pub(crate) fn send_json(self, data: impl Serialize) -> SendResult {
+        let data = serde_json::to_value(data).unwrap();
+        self.send(|request| request.send_json(&data).map_err(Box::new))
+    }
+
+    /// Sends a mutating request again after a failure which might have happened after the server
+    /// processed it, unless `applied` finds it already took effect
+    pub(crate) fn send_json_verified<T, F>(self, data: impl Serialize, applied: F) -> Result<T>
+    where
+        T: DeserializeOwned,
+        F: Fn() -> Result<Option<T>>,
+    {
+        let data = serde_json::to_value(data).unwrap();
+        let mut attempt = 0;
+        loop {
+            let error = match self.clone().send_json(&data) {
+                Ok(response) => return Ok(response.into_json()?),
+                Err(error) if classify(&error) == Failure::Transient => error,
+                Err(error) => return Err(error.into()),
+            };
+            let delay = Some(attempt)
+                .filter(|attempt| *attempt < self.policy.max_retries)
+                .and_then(|attempt| self.policy.delay(attempt, retry_after(&error)));
+            // Waiting first gives a request which is still processed the time to take effect
+            if let Some(delay) = delay {
+                thread::sleep(delay);
+            }
+            if let Some(applied) = applied()? {
+                return Ok(applied);
+            }
+            if delay.is_none() {
+                return Err(error.into());
+            }
+            attempt += 1;
+        }
+    }
+
+    fn send<F>(self, send: F) -> SendResult
+    where
+        F: Fn(ureq::Request) -> SendResult,
+    {
+        let safe = self.inner.method() == "GET";
+        let mut attempt = 0;
+        loop {
+            match send(self.inner.clone()) {
+                Err(error) if attempt < self.policy.max_retries => {
+                    let retry = match classify(&error) {
+                        Failure::NotSent => true,
+                        Failure::Transient => safe,
+                        Failure::Permanent => false,
+                    };
+                    let delay = self.policy.delay(attempt, retry_after(&error));
+                    match delay {
+                        Some(delay) if retry => thread::sleep(delay),
+                        _ => return Err(error),
+                    }
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delay_grows_exponentially_with_jitter() {
+        let policy = RetryPolicy::default();
+        for attempt in 0..3 {
+            let full = Duration::from_millis(250 * 2u64.pow(attempt));
+            let delay = policy.delay(attempt, None).unwrap();
+            assert!(delay >= full / 2 && delay <= full, "{delay:?}");
+        }
+        assert!(policy.delay(10, None).unwrap() <= Duration::from_secs(8));
+    }
+
+    #[test]
+    fn it_parses_retry_after() {
+        let now = time::macros::datetime!(2026-10-17 07:28 UTC);
+        assert_eq!(parse_retry_after("120", now), Some(Duration::from_secs(120)));
+        assert_eq!(
+            parse_retry_after("Sat, 17 Oct 2026 07:28:30 GMT", now),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            parse_retry_after("Sat, 17 Oct 2026 07:00:00 GMT", now),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
+    #[test]
+    fn delay_respects_retry_after() {
+        let policy = RetryPolicy::default();
+        assert_eq!(
+            policy.delay(0, Some(Duration::from_secs(2))),
+            Some(Duration::from_secs(2))
+        );
+        assert_eq!(policy.delay(0, Some(Duration::from_secs(60))), None);
+    }
+}
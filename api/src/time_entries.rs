@@ -1,12 +1,19 @@
+use std::collections::HashSet;
+
 use json_value_merge::Merge;
-use serde::{Deserialize, Deserializer, Serialize};
+use serde::{Deserialize, Serialize};
 use serde_json::json;
-use time::format_description::FormatItem;
+use time::format_description::{well_known::Rfc3339, FormatItem};
 use time::macros::format_description;
 use time::serde::rfc3339;
-use time::{Duration, OffsetDateTime};
+use time::{Date, Duration, OffsetDateTime, UtcOffset};
+
+use super::{Error, Result};
 
-use super::Result;
+/// Number of entries requested per page when listing time entries
+const PAGE_SIZE: usize = 50;
+/// Upper limit of pages fetched, in case the server does not honor `limit` and `offset`
+const MAX_PAGES: usize = 100;
 
 impl super::Client {
     pub fn create_time_entry(&self, entry: &NewTimeEntry) -> Result<TimeEntry> {
@@ -19,6 +26,28 @@ impl super::Client {
         Result::Ok(entry)
     }
 
+    pub fn time_entry(&self, id: &str) -> Result<TimeEntry> {
+        let entry: TimeEntry = self
+            .get(&format!("time_tracking/api/time_entries/{id}"))
+            .call()?
+            .into_json()?;
+        Result::Ok(entry)
+    }
+
+    /// Replaces shifts, breaks and notes of an existing entry
+    pub fn update_time_entry(&self, id: &str, entry: &NewTimeEntry) -> Result<TimeEntry> {
+        let entry: TimeEntry = self
+            .patch(&format!("time_tracking/api/time_entries/{id}"))
+            .send_json(json!(&entry))?
+            .into_json()?;
+        Result::Ok(entry)
+    }
+
+    pub fn delete_time_entry(&self, id: &str) -> Result<()> {
+        self.delete(&format!("time_tracking/api/time_entries/{id}")).call()?;
+        Result::Ok(())
+    }
+
     pub fn current_time_entry(&self) -> Result<Option<TimeEntry>> {
         // Role must be present as URL parameter, otherwise Managers might see employees records as status
         let query: Vec<(&str, &str)> = vec![("endTime", ""), ("role", self.role().expect("Missing Role"))];
@@ -30,36 +59,88 @@ impl super::Client {
         Result::Ok(entries.into_iter().next())
     }
 
+    /// Lists all time entries of the given role which started between `from` and `to` (inclusive)
+    pub fn time_entries(&self, role: &str, from: Date, to: Date) -> Result<Vec<TimeEntry>> {
+        let (from, to, limit) = (from.to_string(), to.to_string(), PAGE_SIZE.to_string());
+        let mut entries: Vec<TimeEntry> = Vec::new();
+        let mut ids = HashSet::new();
+        for page_number in 0..MAX_PAGES {
+            let offset = (page_number * PAGE_SIZE).to_string();
+            let query: Vec<(&str, &str)> = vec![
+                ("role", role),
+                ("startDate__gte", &from),
+                ("startDate__lte", &to),
+                ("limit", &limit),
+                ("offset", &offset),
+            ];
+            let page: Vec<TimeEntry> = self
+                .get("time_tracking/api/time_entries")
+                .query_pairs(query)
+                .call()?
+                .into_json()?;
+            let last_page = page.len() < PAGE_SIZE;
+            let count = entries.len();
+            entries.extend(page.into_iter().filter(|e| ids.insert(e.id.clone())));
+            // A page without new entries means the server ignored the offset
+            if last_page || entries.len() == count {
+                return Result::Ok(entries);
+            }
+        }
+        Err(Error::Generic(format!(
+            "More than {} time entries between {from} and {to}, query a shorter range",
+            MAX_PAGES * PAGE_SIZE
+        )))
+    }
+
     pub fn start_break(&self, id: &str, break_type_id: &str) -> Result<TimeEntry> {
-        let entry: TimeEntry = self
-            .post(&format!("time_tracking/api/time_entries/{id}/start_break"))
-            .send_json(ureq::json!({"source": "WEB_CLOCK", "break_type": break_type_id}))?
-            .into_json()?;
-        Result::Ok(entry)
+        self.post_verified(
+            &format!("time_tracking/api/time_entries/{id}/start_break"),
+            json!({"source": "WEB_CLOCK", "break_type": break_type_id}),
+            || Ok(Some(self.time_entry(id)?).filter(|e| e.current_break().is_some())),
+        )
     }
 
     pub fn end_break(&self, id: &str, break_type_id: &str) -> Result<TimeEntry> {
-        let entry: TimeEntry = self
-            .post(&format!("time_tracking/api/time_entries/{id}/end_break"))
-            .send_json(ureq::json!({"source": "WEB_CLOCK", "break_type": break_type_id}))?
-            .into_json()?;
-        Result::Ok(entry)
+        self.post_verified(
+            &format!("time_tracking/api/time_entries/{id}/end_break"),
+            json!({"source": "WEB_CLOCK", "break_type": break_type_id}),
+            || Ok(Some(self.time_entry(id)?).filter(|e| e.current_break().is_none())),
+        )
     }
 
     pub fn start_clock(&self) -> Result<TimeEntry> {
+        self.post_verified(
+            "time_tracking/api/time_entries/start_clock",
+            json!({"source": "WEB_CLOCK", "role": self.role().unwrap()}),
+            || self.current_time_entry(),
+        )
+    }
+
+    /// Moves the start of a running entry, for example after clocking in late
+    pub fn update_start_time(&self, id: &str, start_time: OffsetDateTime) -> Result<TimeEntry> {
+        let start_time = start_time.format(&Rfc3339).unwrap();
         let entry: TimeEntry = self
-            .post("time_tracking/api/time_entries/start_clock")
-            .send_json(ureq::json!({"source": "WEB_CLOCK", "role": self.role().unwrap()}))?
+            .patch(&format!("time_tracking/api/time_entries/{id}"))
+            .send_json(ureq::json!({"jobShifts": [{"startTime": start_time, "endTime": null}]}))?
             .into_json()?;
         Result::Ok(entry)
     }
 
     pub fn end_clock(&self, id: &str) -> Result<TimeEntry> {
-        let entry: TimeEntry = self
-            .post(&format!("time_tracking/api/time_entries/{id}/stop_clock"))
-            .send_json(ureq::json!({"source": "WEB_CLOCK"}))?
-            .into_json()?;
-        Result::Ok(entry)
+        self.post_verified(
+            &format!("time_tracking/api/time_entries/{id}/stop_clock"),
+            json!({"source": "WEB_CLOCK"}),
+            || Ok(Some(self.time_entry(id)?).filter(|e| e.end_time.is_some())),
+        )
+    }
+
+    /// Sends a clock action again after a failure which might have happened after the server
+    /// processed it, unless `applied` finds the entry already in the expected state
+    fn post_verified<F>(&self, path: &str, body: serde_json::Value, applied: F) -> Result<TimeEntry>
+    where
+        F: Fn() -> Result<Option<TimeEntry>>,
+    {
+        self.post(path).send_json_verified(body, applied)
     }
 }
 
@@ -68,42 +149,48 @@ pub struct NewTimeEntry {
     #[serde(rename = "jobShifts")]
     pub shifts: Vec<NewTimeEntryShift>,
     pub breaks: Vec<NewTimeEntryBreak>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
     source: String,
 }
 
 const DATE_FMT: &[FormatItem] = format_description!("[weekday repr:short] [day] [month repr:short]");
 const TIME_FMT: &[FormatItem] = format_description!("[hour]:[minute]");
 
-impl NewTimeEntry {
-    fn render_breaks(&self) -> String {
-        self.breaks.iter().fold(String::new(), |mut a, b| {
-            if !a.is_empty() {
-                a.push_str(", ");
-            }
-            a.push_str(&format!(
-                "{}-{}",
-                b.start_time.format(TIME_FMT).unwrap(),
-                b.end_time.format(TIME_FMT).unwrap()
-            ));
-            a
-        })
+fn render_entry(
+    start_time: OffsetDateTime,
+    end_time: Option<OffsetDateTime>,
+    breaks: &[(OffsetDateTime, Option<OffsetDateTime>)],
+) -> String {
+    let format_time = |time: Option<OffsetDateTime>| time.map_or("?".into(), |t| t.format(TIME_FMT).unwrap());
+    let mut out = format!(
+        "{} {}-{}",
+        start_time.date().format(DATE_FMT).unwrap(),
+        format_time(Some(start_time)),
+        format_time(end_time)
+    );
+    if !breaks.is_empty() {
+        let breaks: Vec<String> = breaks
+            .iter()
+            .map(|(start, end)| format!("{}-{}", format_time(Some(*start)), format_time(*end)))
+            .collect();
+        out.push_str(&format!(" (Breaks {})", breaks.join(", ")));
     }
+    out
 }
 
 impl std::fmt::Display for NewTimeEntry {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let shift = self.shifts.first().unwrap();
-        let date = shift.start_time.date();
-        let mut out = format!(
-            "{} {}-{}",
-            date.format(DATE_FMT).unwrap(),
-            shift.start_time.format(TIME_FMT).unwrap(),
-            shift.end_time.format(TIME_FMT).unwrap()
-        );
-        if !self.breaks.is_empty() {
-            out.push_str(&format!(" (Breaks {})", self.render_breaks()));
-        }
-        write!(f, "{out}")
+        let breaks: Vec<_> = self.breaks.iter().map(|b| (b.start_time, Some(b.end_time))).collect();
+        write!(f, "{}", render_entry(shift.start_time, Some(shift.end_time), &breaks))
+    }
+}
+
+impl std::fmt::Display for TimeEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let breaks: Vec<_> = self.breaks.iter().map(|b| (b.start_time, b.end_time)).collect();
+        write!(f, "{}", render_entry(self.start_time, self.end_time, &breaks))
     }
 }
 
@@ -134,11 +221,19 @@ pub struct TimeEntry {
     pub start_time: OffsetDateTime,
     #[serde(rename = "endTime", with = "rfc3339::option")]
     pub end_time: Option<OffsetDateTime>,
+    #[serde(rename = "startDate")]
+    pub start_date: Date,
+    #[serde(rename = "jobShifts")]
+    pub shifts: Vec<TimeEntryShift>,
     pub breaks: Vec<TimeEntryBreak>,
-    #[serde(rename = "regularHours", deserialize_with = "f32_from_str")]
+    #[serde(rename = "regularHours", deserialize_with = "crate::f32_from_str")]
     pub regular_hours: f32,
-    #[serde(rename = "unpaidBreakHours", deserialize_with = "f32_from_str")]
+    #[serde(rename = "overtimeHours", deserialize_with = "crate::f32_from_str")]
+    pub overtime_hours: f32,
+    #[serde(rename = "unpaidBreakHours", deserialize_with = "crate::f32_from_str")]
     pub unpaid_break_hours: f32,
+    pub status: String,
+    pub notes: Option<String>,
     // pub timezone: String,
 }
 
@@ -150,6 +245,14 @@ pub struct TimeEntryActivePolicy {
     pub break_policy_id: String,
 }
 
+#[derive(Clone, Debug, Deserialize)]
+pub struct TimeEntryShift {
+    #[serde(rename = "startTime", with = "rfc3339")]
+    pub start_time: OffsetDateTime,
+    #[serde(rename = "endTime", with = "rfc3339::option")]
+    pub end_time: Option<OffsetDateTime>,
+}
+
 #[derive(Clone, Debug, Deserialize)]
 pub struct TimeEntryBreak {
     #[serde(rename = "companyBreakType")]
@@ -163,7 +266,7 @@ pub struct TimeEntryBreak {
 
 impl NewTimeEntry {
     pub fn new() -> Self {
-        Self { shifts: Vec::new(), breaks: Vec::new(), source: "WEB".into() }
+        Self { shifts: Vec::new(), breaks: Vec::new(), notes: None, source: "WEB".into() }
     }
 
     pub fn add_shift(&mut self, start_time: OffsetDateTime, end_time: OffsetDateTime) {
@@ -176,7 +279,29 @@ impl NewTimeEntry {
     }
 }
 
+impl Default for NewTimeEntry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl TimeEntry {
+    /// Converts all timestamps of this entry to the given offset, for example for display
+    pub fn to_offset(&self, offset: UtcOffset) -> Self {
+        let mut entry = self.clone();
+        entry.start_time = entry.start_time.to_offset(offset);
+        entry.end_time = entry.end_time.map(|t| t.to_offset(offset));
+        for shift in entry.shifts.iter_mut() {
+            shift.start_time = shift.start_time.to_offset(offset);
+            shift.end_time = shift.end_time.map(|t| t.to_offset(offset));
+        }
+        for br in entry.breaks.iter_mut() {
+            br.start_time = br.start_time.to_offset(offset);
+            br.end_time = br.end_time.map(|t| t.to_offset(offset));
+        }
+        entry
+    }
+
     pub fn current_break(&self) -> Option<&TimeEntryBreak> {
         self.breaks.iter().find(|b| b.end_time.is_none())
     }
@@ -188,20 +313,15 @@ impl TimeEntryBreak {
     }
 }
 
-fn f32_from_str<'de, D>(deserializer: D) -> std::result::Result<f32, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    let s = String::deserialize(deserializer).unwrap();
-    Ok(s.parse::<f32>().unwrap())
-}
-
 #[cfg(test)]
 mod tests {
     use crate::Client;
 
     use super::*;
-    use time::{format_description::well_known::Rfc3339, macros::datetime, UtcOffset};
+    use time::{
+        macros::{date, datetime},
+        UtcOffset,
+    };
     use utilities::mocking;
 
     fn setup() -> (mocking::FakeRippling, Client) {
@@ -253,6 +373,62 @@ mod tests {
         m.assert();
     }
 
+    #[test]
+    fn it_can_update_entries() {
+        let (mut server, client) = setup();
+        let mut new_entry = NewTimeEntry::new();
+        new_entry.add_shift(datetime!(2023-01-19 09:00 +1), datetime!(2023-01-19 17:00 +1));
+        new_entry.notes = Some("Forgot to clock out".into());
+
+        let m = server
+            .with_fixture("PATCH", "/time_tracking/api/time_entries/some-entry-id", "entry_closed")
+            .match_body(mocking::Matcher::Json(json!(
+                {
+                    "jobShifts": [
+                        {
+                            "startTime": "2023-01-19T09:00:00+01:00",
+                            "endTime": "2023-01-19T17:00:00+01:00"
+                        }
+                    ],
+                    "breaks": [],
+                    "notes": "Forgot to clock out",
+                    "source": "WEB"
+                }
+            )))
+            .create();
+
+        let entry = client.update_time_entry("some-entry-id", &new_entry).unwrap();
+        assert_eq!(entry.id, "some-entry-id");
+        m.assert();
+    }
+
+    #[test]
+    fn it_can_fetch_an_entry() {
+        let (mut server, client) = setup();
+        let _m = server
+            .with_fixture("GET", "/time_tracking/api/time_entries/some-entry-id", "entry_closed")
+            .create();
+
+        let entry = client.time_entry("some-entry-id").unwrap();
+        assert_eq!(
+            entry.to_offset(UtcOffset::UTC).to_string(),
+            "Thu 19 Jan 08:22-16:22 (Breaks 12:00-12:30)"
+        );
+    }
+
+    #[test]
+    fn it_can_delete_entries() {
+        let (mut server, client) = setup();
+        let m = server
+            .mock("DELETE", "/time_tracking/api/time_entries/some-entry-id")
+            .with_status(204)
+            .match_header("authorization", "Bearer access-token")
+            .create();
+
+        client.delete_time_entry("some-entry-id").unwrap();
+        m.assert();
+    }
+
     #[test]
     fn it_can_fetch_current_entry() {
         let (mut server, client) = setup();
@@ -274,6 +450,231 @@ mod tests {
         assert!(entry.current_break().is_none());
     }
 
+    #[test]
+    fn it_retries_reads_after_server_errors() {
+        let (mut server, client) = setup();
+        let path = "/time_tracking/api/time_entries?endTime=&role=some-role-id";
+        let failed = server
+            .mock("GET", path)
+            .with_status(503)
+            .with_header("Retry-After", "0")
+            .expect(1)
+            .create();
+        let ok = server.with_fixture("GET", path, "time_entries").create();
+
+        assert!(client.current_time_entry().unwrap().is_some());
+        failed.assert();
+        ok.assert();
+    }
+
+    #[test]
+    fn it_does_not_repeat_creating_entries_after_server_errors() {
+        let (mut server, client) = setup();
+        let m = server
+            .mock("POST", "/time_tracking/api/time_entries")
+            .with_status(503)
+            .expect(1)
+            .create();
+
+        let mut new_entry = NewTimeEntry::new();
+        new_entry.add_shift(datetime!(2023-01-20 08:00 +1), datetime!(2023-01-20 17:00 +1));
+        assert!(client.create_time_entry(&new_entry).is_err());
+        m.assert();
+    }
+
+    #[test]
+    fn it_checks_the_clock_before_clocking_in_again() {
+        let (mut server, client) = setup();
+        let post = server
+            .mock("POST", "/time_tracking/api/time_entries/start_clock")
+            .with_status(502)
+            .expect(1)
+            .create();
+        let get = server
+            .with_fixture(
+                "GET",
+                "/time_tracking/api/time_entries?endTime=&role=some-role-id",
+                "time_entries",
+            )
+            .create();
+
+        let entry = client.start_clock().unwrap();
+        assert_eq!(entry.id, "some-entry-id");
+        post.assert();
+        get.assert();
+    }
+
+    fn setup_with_policy(policy: crate::RetryPolicy) -> (mocking::FakeRippling, Client) {
+        let server = mocking::FakeRippling::new();
+        let client = crate::ClientBuilder::new()
+            .root(url::Url::parse(&server.url()).unwrap())
+            .retry_policy(policy)
+            .build("access-token".to_owned())
+            .with_company_and_role("some-company-id".to_owned(), "some-role-id".to_owned());
+        (server, client)
+    }
+
+    #[test]
+    fn it_fails_right_away_when_asked_to_wait_too_long() {
+        let (mut server, client) = setup();
+        let m = server
+            .mock("GET", "/time_tracking/api/time_entries?endTime=&role=some-role-id")
+            .with_status(429)
+            .with_header("Retry-After", "60")
+            .expect(1)
+            .create();
+
+        let error = client.current_time_entry().unwrap_err();
+        assert!(matches!(error, Error::RetryAfter { status: 429, wait } if wait.as_secs() == 60));
+        m.assert();
+    }
+
+    #[test]
+    fn it_does_not_repeat_clock_actions_without_retries() {
+        let (mut server, client) = setup_with_policy(crate::RetryPolicy::none());
+        let post = server
+            .mock("POST", "/time_tracking/api/time_entries/start_clock")
+            .with_status(502)
+            .expect(1)
+            .create();
+        let get = server
+            .with_body(
+                "GET",
+                "/time_tracking/api/time_entries?endTime=&role=some-role-id",
+                "[]",
+            )
+            .expect(1)
+            .create();
+
+        assert!(client.start_clock().is_err());
+        post.assert();
+        get.assert();
+    }
+
+    #[test]
+    fn it_repeats_clock_actions_as_often_as_the_policy_allows() {
+        let (mut server, client) = setup_with_policy(crate::RetryPolicy {
+            max_retries: 2,
+            base_delay: std::time::Duration::from_millis(1),
+            max_delay: std::time::Duration::from_millis(1),
+        });
+        let post = server
+            .mock("POST", "/time_tracking/api/time_entries/start_clock")
+            .with_status(502)
+            .expect(3)
+            .create();
+        let get = server
+            .with_body(
+                "GET",
+                "/time_tracking/api/time_entries?endTime=&role=some-role-id",
+                "[]",
+            )
+            .expect(3)
+            .create();
+
+        assert!(client.start_clock().is_err());
+        post.assert();
+        get.assert();
+    }
+
+    #[test]
+    fn it_can_fetch_entries_of_a_date_range() {
+        let (mut server, client) = setup();
+        let body = format!("[{}]", mocking::read_fixture("entry_closed"));
+        let m = server
+            .with_body(
+                "GET",
+                "/time_tracking/api/time_entries?role=some-role-id&startDate__gte=2023-01-16&startDate__lte=2023-01-22&limit=50&offset=0",
+                &body,
+            )
+            .create();
+
+        let entries = client
+            .time_entries("some-role-id", date!(2023 - 01 - 16), date!(2023 - 01 - 22))
+            .unwrap();
+        m.assert();
+        assert_eq!(entries.len(), 1);
+        let entry = entries.first().unwrap();
+        assert_eq!(entry.start_date, date!(2023 - 01 - 19));
+        assert_eq!(entry.end_time.unwrap(), datetime!(2023-01-19 16:22:25 UTC));
+        assert_eq!(entry.shifts.len(), 1);
+        assert_eq!(entry.breaks.first().unwrap().duration(), Some(Duration::minutes(30)));
+        assert_eq!(entry.regular_hours, 7.5);
+        assert_eq!(entry.overtime_hours, 0.0);
+        assert_eq!(entry.status, "APPROVED");
+    }
+
+    fn entries_page(ids: std::ops::Range<usize>) -> String {
+        let entry: serde_json::Value = serde_json::from_str(&mocking::read_fixture("entry_closed")).unwrap();
+        let page: Vec<serde_json::Value> = ids
+            .map(|id| {
+                let mut entry = entry.clone();
+                entry["id"] = format!("entry-{id}").into();
+                entry
+            })
+            .collect();
+        serde_json::to_string(&page).unwrap()
+    }
+
+    #[test]
+    fn it_can_fetch_entries_over_multiple_pages() {
+        let (mut server, client) = setup();
+        let path = "/time_tracking/api/time_entries?role=some-role-id&startDate__gte=2023-01-01&startDate__lte=2023-01-31&limit=50";
+        let m1 = server
+            .with_body("GET", &format!("{path}&offset=0"), &entries_page(0..PAGE_SIZE))
+            .create();
+        let m2 = server
+            .with_body(
+                "GET",
+                &format!("{path}&offset=50"),
+                &entries_page(PAGE_SIZE..PAGE_SIZE + 1),
+            )
+            .create();
+
+        let entries = client
+            .time_entries("some-role-id", date!(2023 - 01 - 01), date!(2023 - 01 - 31))
+            .unwrap();
+        m1.assert();
+        m2.assert();
+        assert_eq!(entries.len(), PAGE_SIZE + 1);
+    }
+
+    #[test]
+    fn it_fails_instead_of_truncating_after_the_last_page() {
+        let (mut server, client) = setup();
+        let m = server
+            .mock("GET", mocking::Matcher::Any)
+            .with_header("content-type", "application/json")
+            .with_body_from_request(|request| {
+                let query = request.path_and_query();
+                let offset: usize = query.rsplit("offset=").next().unwrap().parse().unwrap();
+                entries_page(offset..offset + PAGE_SIZE).into_bytes()
+            })
+            .expect(MAX_PAGES)
+            .create();
+
+        let result = client.time_entries("some-role-id", date!(2023 - 01 - 01), date!(2023 - 01 - 31));
+        m.assert();
+        assert!(matches!(result, Err(Error::Generic(_))));
+    }
+
+    #[test]
+    fn it_stops_paging_when_the_server_ignores_the_offset() {
+        let (mut server, client) = setup();
+        let m = server
+            .mock("GET", mocking::Matcher::Any)
+            .with_header("content-type", "application/json")
+            .with_body(entries_page(0..PAGE_SIZE))
+            .expect(2)
+            .create();
+
+        let entries = client
+            .time_entries("some-role-id", date!(2023 - 01 - 01), date!(2023 - 01 - 31))
+            .unwrap();
+        m.assert();
+        assert_eq!(entries.len(), PAGE_SIZE);
+    }
+
     #[test]
     fn it_can_start_the_clock() {
         let (mut server, client) = setup();
@@ -293,6 +694,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn it_can_move_the_start_time() {
+        let (mut server, client) = setup();
+        let m = server
+            .with_fixture("PATCH", "/time_tracking/api/time_entries/id", "time_entry")
+            .match_body(mocking::Matcher::Json(json!(
+                {"jobShifts": [{"startTime": "2023-01-19T08:00:00+01:00", "endTime": null}]}
+            )))
+            .create();
+
+        client.update_start_time("id", datetime!(2023-01-19 08:00 +1)).unwrap();
+        m.assert();
+    }
+
     #[test]
     fn it_can_stop_the_clock() {
         let (mut server, client) = setup();
@@ -1,18 +1,31 @@
+pub mod calendar;
+pub mod delete;
+pub mod edit;
+pub mod export;
+pub mod import;
 pub mod live;
+pub mod log;
 pub mod manual_entry;
+pub mod output;
 pub mod pto;
 
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
 use clap::Subcommand;
 use core::time::Duration;
 use indicatif::ProgressBar;
+use inquire::{Confirm, InquireError, Select};
+use regex::Regex;
 use rippling_api::{self, Client};
-use time::{macros::format_description, Date, OffsetDateTime, PrimitiveDateTime, UtcOffset};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use time::{macros::format_description, Date, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
 
-use crate::persistence::State;
+use crate::persistence::{self, State};
 
 use self::pto::CheckOutcome;
 
 const FORMAT_R: &[time::format_description::FormatItem] = format_description!("[hour]:[minute]");
+const FORMAT_DATE: &[time::format_description::FormatItem] = format_description!("[year]-[month]-[day]");
 
 #[derive(Debug, Subcommand)]
 pub enum Commands {
@@ -27,15 +40,29 @@ pub enum Commands {
         /// Compact format
         #[arg(short, long, default_value_t = false)]
         compact: bool,
+        /// Warn when the access token expires within this many days
+        #[arg(long, default_value_t = 7)]
+        token_warning_days: u16,
     },
 
     /// Clock In
     #[clap(alias = "in")]
-    ClockIn,
+    ClockIn {
+        /// Clock in at this earlier local time instead of now, for example 8:30
+        #[arg(long, value_parser = parse_time)]
+        at: Option<Time>,
+    },
 
     /// Clock Out
     #[clap(alias = "out")]
-    ClockOut,
+    ClockOut {
+        /// Clock out at this local time instead of now, for example 17:30
+        #[arg(long, value_parser = parse_time)]
+        at: Option<Time>,
+        /// Clock out yesterday, at the time given by --at
+        #[arg(long, requires = "at")]
+        yesterday: bool,
+    },
 
     /// Start a break
     #[clap(alias = "sb", alias = "break")]
@@ -47,24 +74,94 @@ pub enum Commands {
 
     /// Manually add entry for a day
     Manual(manual_entry::Command),
+
+    /// List past entries with daily and weekly totals
+    Log(log::Command),
+
+    /// Edit an existing entry
+    Edit(edit::Command),
+
+    /// Delete an existing entry
+    Delete(delete::Command),
+
+    /// Month overview with holidays, PTO and worked hours
+    Calendar(calendar::Command),
+
+    /// Export data into other formats
+    Export {
+        #[command(subcommand)]
+        command: export::Command,
+    },
+
+    /// Import entries from other formats
+    Import {
+        #[command(subcommand)]
+        command: import::Command,
+    },
+
+    /// Holidays and PTO
+    Pto {
+        #[command(subcommand)]
+        command: pto::Command,
+    },
+}
+
+impl Commands {
+    /// Whether the command only reads, so running it again after a failure cannot repeat a change
+    pub fn is_read_only(&self) -> bool {
+        match self {
+            Self::Status { .. } | Self::Log(_) | Self::Calendar(_) | Self::Export { .. } => true,
+            Self::Configure { command } => matches!(command, ConfigureCommands::Show),
+            Self::Pto { command } => !matches!(command, pto::Command::Cancel { .. } | pto::Command::Request(_)),
+            Self::ClockIn { .. }
+            | Self::ClockOut { .. }
+            | Self::StartBreak
+            | Self::EndBreak
+            | Self::Manual(_)
+            | Self::Edit(_)
+            | Self::Delete(_)
+            | Self::Import { .. } => false,
+        }
+    }
 }
 
 #[derive(Debug, Subcommand)]
 pub enum ConfigureCommands {
-    AccessToken { value: String },
+    AccessToken {
+        value: String,
+    },
+    /// Trust the certificates in this PEM file, for example of a TLS-intercepting proxy
+    CaCertificates {
+        path: PathBuf,
+    },
+    /// Show the configured company, role and when the access token expires
+    Show,
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
 
 #[derive(Debug)]
+#[allow(clippy::enum_variant_names)]
 pub enum Error {
     ApiError(rippling_api::Error),
     AlreadyOnBreak,
+    EntryNotFound,
+    InvalidFile(String),
+    InvalidTime(String),
+    Io(std::io::Error),
     NotClockedIn,
     NotOnBreak,
     NoManualBreakType,
+    UnknownLeaveType(String),
     UnexpectedResponse,
     NoWorkingDay(CheckOutcome),
+    /// Entries of these days could not be created, the other days were
+    DaysFailed(Vec<Date>),
+    /// No role stored, the access token was never configured
+    NoRole,
+    /// The user cancelled a prompt
+    Aborted,
+    Prompt(String),
 }
 
 impl std::fmt::Display for Error {
@@ -72,46 +169,123 @@ impl std::fmt::Display for Error {
         match self {
             Self::ApiError(e) => write!(f, "{e}"),
             Self::AlreadyOnBreak => write!(f, "Already on a break"),
+            Self::EntryNotFound => write!(f, "No matching entry found"),
+            Self::InvalidFile(reason) => write!(f, "Invalid file: {reason}"),
+            Self::InvalidTime(reason) => write!(f, "Invalid time: {reason}"),
+            Self::Io(e) => write!(f, "{e}"),
             Self::NotClockedIn => write!(f, "Not clocked in"),
             Self::NotOnBreak => write!(f, "Not on a break"),
             Self::NoManualBreakType => write!(f, "No manual break type"),
+            Self::UnknownLeaveType(name) => write!(f, "Unknown leave type {name}"),
             Self::UnexpectedResponse => write!(f, "Unexpected response received"),
-            Self::NoWorkingDay(r) => match r {
-                CheckOutcome::Leave => write!(f, "You are on PTO"),
-                CheckOutcome::Holiday(h) => write!(f, "It is a holiday ({})", h.name),
-                CheckOutcome::Weekend(d) => write!(f, "It is a weekend ({d})"),
-                _ => panic!("Unhandled enum match"),
-            },
+            Self::NoWorkingDay(r) => write!(f, "{r}"),
+            Self::DaysFailed(dates) => {
+                let dates: Vec<String> = dates.iter().map(|d| d.format(FORMAT_DATE).unwrap()).collect();
+                write!(f, "Could not create the entries of {}", dates.join(", "))
+            }
+            Self::NoRole => write!(
+                f,
+                "No role configured, run `rippling-cli configure access-token <token>` first"
+            ),
+            Self::Aborted => write!(f, "Aborted"),
+            Self::Prompt(reason) => write!(f, "Cannot ask for input: {reason}"),
         }
     }
 }
 
+impl Error {
+    /// The access token is invalid or expired, a new one is needed
+    pub fn is_unauthorized(&self) -> bool {
+        matches!(self, Self::ApiError(rippling_api::Error::Unauthorized))
+    }
+}
+
 impl From<rippling_api::Error> for Error {
     fn from(value: rippling_api::Error) -> Self {
         Error::ApiError(value)
     }
 }
 
+impl From<InquireError> for Error {
+    fn from(value: InquireError) -> Self {
+        match value {
+            InquireError::OperationCanceled | InquireError::OperationInterrupted => Error::Aborted,
+            other => Error::Prompt(other.to_string()),
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(value: std::io::Error) -> Self {
+        Error::Io(value)
+    }
+}
+
 pub fn execute(command: &Commands) -> Result<()> {
+    let client = || Client::from(persistence::state());
     match command {
-        Commands::ClockIn => live::clock_in(),
-        Commands::ClockOut => live::clock_out(),
-        Commands::Status { compact } => {
+        Commands::ClockIn { at } => match at {
+            Some(time) => live::clock_in_at(&client(), *time),
+            None => live::clock_in(&client()),
+        },
+        Commands::ClockOut { at, yesterday } => match at {
+            Some(time) => {
+                let date = if *yesterday {
+                    today().previous_day().unwrap()
+                } else {
+                    today()
+                };
+                live::clock_out_at(&client(), date, *time)
+            }
+            None => live::clock_out(&client()),
+        },
+        Commands::Status { compact, token_warning_days } => {
+            warn_token_expiry(*token_warning_days);
             if *compact {
-                live::status_compact()
+                live::status_compact(&client())
             } else {
-                live::status()
+                live::status(&client())
             }
         }
-        Commands::StartBreak => live::start_break(),
-        Commands::EndBreak => live::end_break(),
+        Commands::StartBreak => live::start_break(&client()),
+        Commands::EndBreak => live::end_break(&client()),
         Commands::Configure { command } => match command {
             ConfigureCommands::AccessToken { value } => set_access_token(value),
+            ConfigureCommands::CaCertificates { path } => set_ca_certificates(path),
+            ConfigureCommands::Show => show_configuration(),
         },
         Commands::Manual(cmd) => manual_entry::execute(cmd),
+        Commands::Log(cmd) => log::execute(cmd),
+        Commands::Edit(cmd) => edit::execute(cmd),
+        Commands::Delete(cmd) => delete::execute(cmd),
+        Commands::Pto { command } => pto::execute(command),
+        Commands::Calendar(cmd) => calendar::execute(cmd),
+        Commands::Export { command } => export::execute(command),
+        Commands::Import { command } => import::execute(command),
+    }
+}
+
+/// The role the commands act as
+fn role(client: &Client) -> Result<&str> {
+    client.role().map(String::as_str).ok_or(Error::NoRole)
+}
+
+/// Asks a yes/no question, cancelling the prompt answers no
+fn confirm(question: &str) -> Result<bool> {
+    match Confirm::new(question).prompt() {
+        Ok(answer) => Ok(answer),
+        Err(e) => match Error::from(e) {
+            Error::Aborted => Ok(false),
+            other => Err(other),
+        },
     }
 }
 
+/// Lets the user pick one of `options`, cancelling the prompt aborts the command
+fn select<T: std::fmt::Display>(message: &str, options: Vec<T>) -> Result<T> {
+    Ok(Select::new(message, options).prompt()?)
+}
+
 #[macro_export]
 macro_rules! spinner_wrap {
     ( $res: expr ) => {{
@@ -128,14 +302,127 @@ macro_rules! spinner_wrap {
     }};
 }
 
-fn set_access_token(token: &str) -> Result<()> {
-    let client: Client = Client::new(token.to_string());
+pub fn set_access_token(token: &str) -> Result<()> {
+    let client: Client = persistence::state().client_builder().build(token.to_string());
     let info = spinner_wrap!(client.account_info())?;
     let state = State {
         company_id: Some(info.role.company.id),
         role_id: Some(info.id),
         token: Some(token.to_string()),
+        token_expires_at: token_expiry(token),
+        ..persistence::state()
+    };
+    state.store();
+    Ok(())
+}
+
+/// Expiry claim of a JWT, `None` for other tokens
+fn token_expiry(token: &str) -> Option<OffsetDateTime> {
+    let parts: Vec<&str> = token.split('.').collect();
+    if parts.len() != 3 {
+        return None;
+    }
+    let payload = URL_SAFE_NO_PAD.decode(parts[1].trim_end_matches('=')).ok()?;
+    let claims: serde_json::Value = serde_json::from_slice(&payload).ok()?;
+    OffsetDateTime::from_unix_timestamp(claims.get("exp")?.as_i64()?).ok()
+}
+
+/// Prints a warning to stderr when the stored access token expires within `days`
+fn warn_token_expiry(days: u16) {
+    let Some(expires_at) = persistence::state().token_expires_at else {
+        return;
     };
+    let remaining = expires_at - OffsetDateTime::now_utc();
+    if remaining < time::Duration::days(i64::from(days)) {
+        eprintln!(
+            "Warning: your access token {}, run `rippling-cli configure access-token <token>` with a new one",
+            describe_expiry(expires_at)
+        );
+    }
+}
+
+fn describe_expiry(expires_at: OffsetDateTime) -> String {
+    let remaining = expires_at - OffsetDateTime::now_utc();
+    let local = expires_at.to_offset(local_offset_at(expires_at));
+    let at = format!(
+        "{} {}",
+        local.date().format(FORMAT_DATE).unwrap(),
+        local.time().format(FORMAT_R).unwrap()
+    );
+    if remaining.is_negative() {
+        format!("expired at {at}")
+    } else {
+        format!("expires at {at}, in {} days", remaining.whole_days())
+    }
+}
+
+/// Result of `configure show`, as printed with `--output json|yaml`
+#[derive(Debug, Serialize)]
+struct ConfigurationReport {
+    event: &'static str,
+    company_id: Option<String>,
+    /// Names are only known to the API, without it (for example offline) they are `null`
+    company_name: Option<String>,
+    role_id: Option<String>,
+    role_name: Option<String>,
+    role_email: Option<String>,
+    token_configured: bool,
+    #[serde(with = "time::serde::rfc3339::option")]
+    token_expires_at: Option<OffsetDateTime>,
+    ca_certificates: Option<PathBuf>,
+}
+
+fn show_configuration() -> Result<()> {
+    let state = persistence::state();
+    let info = match state.token {
+        Some(_) => spinner_wrap!(Client::from(state.clone()).account_info()).ok(),
+        None => None,
+    };
+    let report = ConfigurationReport {
+        event: "configuration",
+        company_name: info.as_ref().and_then(|i| i.company_name()).map(str::to_owned),
+        role_name: info.as_ref().and_then(|i| i.role_name()).map(str::to_owned),
+        role_email: info.and_then(|i| i.role.work_email),
+        company_id: state.company_id,
+        role_id: state.role_id,
+        token_configured: state.token.is_some(),
+        token_expires_at: state.token_expires_at,
+        ca_certificates: state.ca_certificates,
+    };
+    output::print(&report, || render_configuration(&report));
+    Ok(())
+}
+
+fn render_configuration(report: &ConfigurationReport) -> String {
+    let describe = |name: Option<String>, id: &Option<String>| match (name, id) {
+        (Some(name), Some(id)) => format!("{name} ({id})"),
+        (None, Some(id)) => id.clone(),
+        (_, None) => "not configured".into(),
+    };
+    let role = report.role_name.as_ref().map(|name| match &report.role_email {
+        Some(email) => format!("{name} <{email}>"),
+        None => name.clone(),
+    });
+    let token = match (report.token_configured, report.token_expires_at) {
+        (false, _) => "not configured".into(),
+        (true, None) => "configured, expiry unknown".into(),
+        (true, Some(expires_at)) => describe_expiry(expires_at),
+    };
+    let mut out = format!(
+        "Company:         {}\nRole:            {}\nAccess token:    {token}\n",
+        describe(report.company_name.clone(), &report.company_id),
+        describe(role, &report.role_id)
+    );
+    if let Some(path) = &report.ca_certificates {
+        out.push_str(&format!("CA certificates: {}\n", path.display()));
+    }
+    out
+}
+
+fn set_ca_certificates(path: &Path) -> Result<()> {
+    let pem = std::fs::read(path)?;
+    rippling_api::ClientBuilder::new().add_ca_certificates(&pem)?;
+    let state = State { ca_certificates: Some(path.canonicalize()?), ..persistence::state() };
     state.store();
     Ok(())
 }
@@ -163,6 +450,40 @@ fn local_time_format(datetime: OffsetDateTime) -> String {
     datetime.to_offset(local_offset()).time().format(&FORMAT_R).unwrap()
 }
 
+fn parse_date(s: &str) -> std::result::Result<Date, String> {
+    Date::parse(s, FORMAT_DATE).map_err(|_| "Dates must be formatted like 2023-01-31".into())
+}
+
+/// Inclusive range of days
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DateRange {
+    pub from: Date,
+    pub to: Date,
+}
+
+/// Parses a single day (2026-12-22) or a range of days (2026-12-22..2026-12-31)
+fn parse_date_range(s: &str) -> std::result::Result<DateRange, String> {
+    let (from, to) = match s.split_once("..") {
+        Some((from, to)) => (parse_date(from)?, parse_date(to)?),
+        None => (parse_date(s)?, parse_date(s)?),
+    };
+    if from > to {
+        return Err("Ranges must start before they end, for example 2026-12-22..2026-12-31".into());
+    }
+    Ok(DateRange { from, to })
+}
+
+fn parse_time(s: &str) -> std::result::Result<Time, String> {
+    let re = Regex::new(r"^(?P<h>\d{1,2})(?::(?P<m>\d{2}))?$").unwrap();
+    re.captures(s)
+        .and_then(|m| {
+            let hour: u8 = m["h"].parse().unwrap();
+            let minute: u8 = m.name("m").map_or(0, |v| v.as_str().parse().unwrap());
+            Time::from_hms(hour, minute, 0).ok()
+        })
+        .ok_or_else(|| "Times must be formatted like 17:30".into())
+}
+
 fn local_offset() -> UtcOffset {
     local_offset_at(OffsetDateTime::now_utc())
 }
@@ -174,6 +495,12 @@ fn local_offset_at(time: OffsetDateTime) -> UtcOffset {
     })
 }
 
+/// Date and time in the local offset at that moment
+fn local_datetime(time: OffsetDateTime) -> PrimitiveDateTime {
+    let local = time.to_offset(local_offset_at(time));
+    PrimitiveDateTime::new(local.date(), local.time())
+}
+
 fn local_offset_estimated_at(time: PrimitiveDateTime) -> UtcOffset {
     let odt = time.assume_offset(local_offset());
     local_offset_at(odt)
@@ -181,7 +508,75 @@ fn local_offset_estimated_at(time: PrimitiveDateTime) -> UtcOffset {
 
 #[cfg(test)]
 mod tests {
-    use time::{macros::datetime, UtcOffset};
+    use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+    use time::{
+        macros::{date, datetime},
+        UtcOffset,
+    };
+
+    use super::{ConfigurationReport, DateRange};
+
+    #[test]
+    fn it_serializes_a_configuration_report() {
+        let report = ConfigurationReport {
+            event: "configuration",
+            company_id: Some("some-company-id".into()),
+            company_name: Some("Some Company".into()),
+            role_id: Some("some-role-id".into()),
+            role_name: None,
+            role_email: None,
+            token_configured: true,
+            token_expires_at: Some(datetime!(2026-11-01 10:00 UTC)),
+            ca_certificates: None,
+        };
+        assert_eq!(
+            serde_json::to_value(&report).unwrap(),
+            serde_json::json!({
+                "event": "configuration",
+                "company_id": "some-company-id",
+                "company_name": "Some Company",
+                "role_id": "some-role-id",
+                "role_name": null,
+                "role_email": null,
+                "token_configured": true,
+                "token_expires_at": "2026-11-01T10:00:00Z",
+                "ca_certificates": null
+            })
+        );
+        let text = super::render_configuration(&report);
+        assert!(text.starts_with("Company:         Some Company (some-company-id)\nRole:            some-role-id\n"));
+    }
+
+    #[test]
+    fn is_read_only() {
+        use super::{pto, Commands};
+
+        assert!(Commands::Pto { command: pto::Command::Balance }.is_read_only());
+        assert!(!Commands::Pto { command: pto::Command::Cancel { yes: true } }.is_read_only());
+        assert!(!Commands::StartBreak.is_read_only());
+    }
+
+    #[test]
+    fn token_expiry() {
+        let claims = URL_SAFE_NO_PAD.encode(r#"{"sub":"some-user","exp":1793527200}"#);
+        let token = format!("eyJhbGciOiJIUzI1NiJ9.{claims}.signature");
+        assert_eq!(super::token_expiry(&token), Some(datetime!(2026-11-01 10:00 UTC)));
+        assert_eq!(super::token_expiry("not-a-jwt"), None);
+    }
+
+    #[test]
+    fn parse_date_range() {
+        assert_eq!(
+            super::parse_date_range("2026-12-22..2026-12-31"),
+            Ok(DateRange { from: date!(2026 - 12 - 22), to: date!(2026 - 12 - 31) })
+        );
+        assert_eq!(
+            super::parse_date_range("2026-12-22"),
+            Ok(DateRange { from: date!(2026 - 12 - 22), to: date!(2026 - 12 - 22) })
+        );
+        assert!(super::parse_date_range("2026-12-31..2026-12-22").is_err());
+        assert!(super::parse_date_range("2026-12-22..").is_err());
+    }
 
     #[test]
     fn test_test_time_offset() {
@@ -0,0 +1,229 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use clap::Parser;
+use console::{Style, StyledObject};
+use rippling_api::Client;
+use serde::Serialize;
+use time::{macros::format_description, Date, Duration, Month};
+
+use crate::{is_interactive, persistence, spinner_wrap};
+
+use super::{format_hours, output, pto, Result};
+
+const CELL_WIDTH: usize = 11;
+const MONTH_FMT: &[time::format_description::FormatItem] = format_description!("[month repr:long] [year]");
+
+/// Month overview with holidays, PTO and worked hours
+#[derive(Debug, Parser)]
+pub struct Command {
+    /// Defaults to the current month, for example 2026-11
+    #[arg(short, long, value_parser = parse_month)]
+    pub month: Option<Date>,
+}
+
+/// What is shown below the number of a day
+#[derive(Clone, Debug, PartialEq)]
+enum Annotation {
+    Holiday(String),
+    Leave(String),
+    Worked(f32),
+    Weekend,
+    Nothing,
+}
+
+/// Result of `calendar`, as printed with `--output json|yaml`
+#[derive(Debug, Serialize)]
+struct CalendarReport {
+    event: &'static str,
+    days: Vec<Day>,
+}
+
+#[derive(Debug, Serialize)]
+struct Day {
+    date: Date,
+    /// One of `holiday`, `leave`, `worked`, `weekend` or `empty`
+    day: &'static str,
+    /// Name of the holiday or leave type
+    name: Option<String>,
+    /// Regular hours worked
+    hours: Option<f32>,
+}
+
+impl Day {
+    fn new(date: Date, annotation: &Annotation) -> Self {
+        let (day, name, hours) = match annotation {
+            Annotation::Holiday(name) => ("holiday", Some(name.clone()), None),
+            Annotation::Leave(name) => ("leave", Some(name.clone()), None),
+            Annotation::Worked(hours) => ("worked", None, Some(*hours)),
+            Annotation::Weekend => ("weekend", None, None),
+            Annotation::Nothing => ("empty", None, None),
+        };
+        Self { date, day, name, hours }
+    }
+}
+
+/// Entrypoint for this module
+pub fn execute(cmd: &Command) -> Result<()> {
+    let first = cmd.month.unwrap_or_else(|| super::today().replace_day(1).unwrap());
+    let last = last_of_month(first);
+
+    let client: Client = persistence::state().into();
+    let (calendar, requests, entries) = spinner_wrap!((
+        client.holiday_calendar()?,
+        client.leave_requests(Some("APPROVED"))?,
+        client.time_entries(super::role(&client)?, first, last)?
+    ));
+
+    let holidays: Vec<_> = calendar
+        .into_iter()
+        .filter(|hy| i32::from(hy.year) == first.year())
+        .flat_map(|hy| hy.holidays)
+        .collect();
+    let mut worked: HashMap<Date, f32> = HashMap::new();
+    for entry in entries {
+        *worked.entry(entry.start_date).or_default() += entry.regular_hours;
+    }
+
+    let annotations: HashMap<Date, Annotation> = days(first, last)
+        .map(|date| {
+            let annotation = if let Some(h) = holidays.iter().find(|h| h.start_date <= date && h.end_date >= date) {
+                Annotation::Holiday(h.name.clone())
+            } else if let Some(r) = requests.iter().find(|r| r.start_date <= date && r.end_date >= date) {
+                Annotation::Leave(r.leave_type_name.clone())
+            } else if let Some(hours) = worked.get(&date) {
+                Annotation::Worked(*hours)
+            } else if pto::is_weekend(date).is_some() {
+                Annotation::Weekend
+            } else {
+                Annotation::Nothing
+            };
+            (date, annotation)
+        })
+        .collect();
+
+    let report = CalendarReport {
+        event: "calendar",
+        days: days(first, last)
+            .map(|date| Day::new(date, &annotations[&date]))
+            .collect(),
+    };
+    output::print(&report, || render(first, &annotations, is_interactive()));
+    Ok(())
+}
+
+fn render(first: Date, annotations: &HashMap<Date, Annotation>, color: bool) -> String {
+    let last = last_of_month(first);
+    let mut out = String::new();
+
+    let title = first.format(MONTH_FMT).unwrap();
+    writeln!(out, "{title:^width$}", width = CELL_WIDTH * 7).unwrap();
+    for day in ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"] {
+        write!(out, "{day:<CELL_WIDTH$}").unwrap();
+    }
+    writeln!(out).unwrap();
+
+    // Start each row on a monday, leaving cells of the previous month empty
+    let mut monday = first - Duration::days(i64::from(first.weekday().number_days_from_monday()));
+    while monday <= last {
+        let week: Vec<Option<Date>> = (0..7)
+            .map(|i| Some(monday + Duration::days(i)).filter(|d| *d >= first && *d <= last))
+            .collect();
+        for date in &week {
+            let number = date.map_or(String::new(), |d| d.day().to_string());
+            write!(out, "{number:<CELL_WIDTH$}").unwrap();
+        }
+        writeln!(out).unwrap();
+        for date in &week {
+            let annotation = date.and_then(|d| annotations.get(&d)).unwrap_or(&Annotation::Nothing);
+            let (text, style) = match annotation {
+                Annotation::Holiday(name) => (name.clone(), Style::new().red()),
+                Annotation::Leave(name) => (name.clone(), Style::new().green()),
+                Annotation::Worked(hours) => (format_hours(*hours), Style::new().cyan()),
+                Annotation::Weekend => ("-".into(), Style::new().dim()),
+                Annotation::Nothing => (String::new(), Style::new()),
+            };
+            let text: String = text.chars().take(CELL_WIDTH - 1).collect();
+            let cell: StyledObject<String> = style.force_styling(color).apply_to(format!("{text:<CELL_WIDTH$}"));
+            write!(out, "{cell}").unwrap();
+        }
+        writeln!(out).unwrap();
+        monday += Duration::weeks(1);
+    }
+    out
+}
+
+fn last_of_month(date: Date) -> Date {
+    date.replace_day(date.month().length(date.year())).unwrap()
+}
+
+fn days(first: Date, last: Date) -> impl Iterator<Item = Date> {
+    (0..=(last - first).whole_days()).map(move |i| first + Duration::days(i))
+}
+
+fn parse_month(s: &str) -> std::result::Result<Date, String> {
+    let err = || "Months must be formatted like 2026-11".to_owned();
+    let (year, month) = s.split_once('-').ok_or_else(err)?;
+    let year: i32 = year.parse().map_err(|_| err())?;
+    let month: u8 = month.parse().map_err(|_| err())?;
+    let month = Month::try_from(month).map_err(|_| err())?;
+    Date::from_calendar_date(year, month, 1).map_err(|_| err())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use time::macros::date;
+
+    use super::*;
+
+    #[test]
+    fn it_renders_a_month() {
+        let annotations = HashMap::from([
+            (date!(2026 - 11 - 02), Annotation::Worked(8.5)),
+            (date!(2026 - 11 - 03), Annotation::Leave("Vacation".into())),
+            (date!(2026 - 11 - 18), Annotation::Holiday("Day of Repentance".into())),
+        ]);
+        let out = render(date!(2026 - 11 - 01), &annotations, false);
+        let lines: Vec<&str> = out.lines().map(str::trim_end).collect();
+        assert_eq!(lines[0].trim(), "November 2026");
+        assert_eq!(lines[2].trim(), "1");
+        assert_eq!(
+            lines[4],
+            "2          3          4          5          6          7          8"
+        );
+        assert_eq!(lines[5], "8:30       Vacation");
+        assert_eq!(lines[9], "                      Day of Rep");
+        assert_eq!(lines.len(), 2 + 2 * 6);
+    }
+
+    #[test]
+    fn it_serializes_a_calendar_report() {
+        let report = CalendarReport {
+            event: "calendar",
+            days: vec![
+                Day::new(date!(2026 - 11 - 01), &Annotation::Weekend),
+                Day::new(date!(2026 - 11 - 02), &Annotation::Worked(8.5)),
+                Day::new(date!(2026 - 11 - 03), &Annotation::Leave("Vacation".into())),
+            ],
+        };
+        assert_eq!(
+            serde_json::to_value(&report).unwrap(),
+            json!({
+                "event": "calendar",
+                "days": [
+                    {"date": "2026-11-01", "day": "weekend", "name": null, "hours": null},
+                    {"date": "2026-11-02", "day": "worked", "name": null, "hours": 8.5},
+                    {"date": "2026-11-03", "day": "leave", "name": "Vacation", "hours": null}
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn parse_month() {
+        assert_eq!(super::parse_month("2026-11"), Ok(date!(2026 - 11 - 01)));
+        assert!(super::parse_month("2026-13").is_err());
+        assert!(super::parse_month("November").is_err());
+    }
+}
@@ -0,0 +1,30 @@
+use clap::Parser;
+use rippling_api::Client;
+
+use crate::{persistence, spinner_wrap};
+
+use super::edit::{self, EntrySelector};
+use super::{output, Result};
+
+/// Delete an existing entry
+#[derive(Debug, Parser)]
+pub struct Command {
+    /// Date (for example 2023-01-19) or id of the entry
+    #[arg(value_parser = edit::parse_selector)]
+    pub entry: EntrySelector,
+    /// Bypass prompt with a yes answer
+    #[arg(short, long)]
+    pub yes: bool,
+}
+
+/// Entrypoint for this module
+pub fn execute(cmd: &Command) -> Result<()> {
+    let client: Client = persistence::state().into();
+    let entry = edit::select_entry(&client, &cmd.entry)?;
+    let entry = entry.to_offset(super::local_offset_at(entry.start_time));
+    if cmd.yes || super::confirm(&format!("Delete entry {entry}?"))? {
+        spinner_wrap!(client.delete_time_entry(&entry.id))?;
+        output::report("delete", Some(&entry), || format!("Deleted entry {entry}"));
+    }
+    Ok(())
+}
@@ -0,0 +1,150 @@
+use clap::Parser;
+use rippling_api::{
+    time_entries::{NewTimeEntry, NewTimeEntryBreak, NewTimeEntryShift, TimeEntry},
+    Client,
+};
+use time::Date;
+
+use crate::{persistence, spinner_wrap};
+
+use super::manual_entry::{self, TimeRange};
+use super::{output, Error, Result};
+
+/// Identifies an existing entry, either by its date or by its id
+#[derive(Clone, Debug)]
+pub enum EntrySelector {
+    Date(Date),
+    Id(String),
+}
+
+/// Edit shifts, breaks and notes of an existing entry
+#[derive(Debug, Parser)]
+pub struct Command {
+    /// Date (for example 2023-01-19) or id of the entry
+    #[arg(value_parser = parse_selector)]
+    pub entry: EntrySelector,
+    /// Replace the notes of the entry
+    #[arg(short, long)]
+    pub note: Option<String>,
+    /// Bypass prompt with a yes answer
+    #[arg(short, long)]
+    pub yes: bool,
+    /// New shifts, breaks are added like for manual entries. Keeps shifts and breaks when omitted
+    #[arg(value_parser = manual_entry::parse_input_shifts, required_unless_present = "note")]
+    pub ranges: Vec<TimeRange>,
+}
+
+/// Entrypoint for this module
+pub fn execute(cmd: &Command) -> Result<()> {
+    if !cmd.ranges.is_empty() {
+        manual_entry::check_ranges(&cmd.ranges).map_err(Error::InvalidTime)?;
+    }
+    let client: Client = persistence::state().into();
+    let existing = select_entry(&client, &cmd.entry)?;
+    let mut entry = if cmd.ranges.is_empty() {
+        keep_times(&existing)?
+    } else {
+        manual_entry::draft_entry(&client, existing.start_date, &cmd.ranges, false)?
+    };
+    entry.notes = cmd.note.clone().or(existing.notes.clone());
+    if cmd.yes || super::confirm(&format!("Update entry to {entry}?"))? {
+        let entry = spinner_wrap!(client.update_time_entry(&existing.id, &entry))?;
+        output::report("edit", Some(&entry), || {
+            format!(
+                "Updated entry to {}",
+                entry.to_offset(super::local_offset_at(entry.start_time))
+            )
+        });
+    }
+    Ok(())
+}
+
+/// The shifts and breaks of `entry` unchanged, to only replace its notes
+fn keep_times(entry: &TimeEntry) -> Result<NewTimeEntry> {
+    let running = || Error::InvalidTime("the entry is still running, give its new shifts".into());
+    let mut new = NewTimeEntry::new();
+    for shift in &entry.shifts {
+        new.shifts
+            .push(NewTimeEntryShift { start_time: shift.start_time, end_time: shift.end_time.ok_or_else(running)? });
+    }
+    for b in &entry.breaks {
+        new.breaks.push(NewTimeEntryBreak {
+            break_type_id: b.break_type_id.clone(),
+            start_time: b.start_time,
+            end_time: b.end_time.ok_or_else(running)?,
+        });
+    }
+    Ok(new)
+}
+
+/// Finds the entry, asking the user to pick one if a date has several entries
+pub(super) fn select_entry(client: &Client, selector: &EntrySelector) -> Result<TimeEntry> {
+    match selector {
+        EntrySelector::Id(id) => Ok(spinner_wrap!(client.time_entry(id))?),
+        EntrySelector::Date(date) => {
+            let mut entries = spinner_wrap!(client.time_entries(super::role(client)?, *date, *date))?;
+            match entries.len() {
+                0 => Err(Error::EntryNotFound),
+                1 => Ok(entries.remove(0)),
+                _ => {
+                    let options: Vec<TimeEntry> = entries
+                        .into_iter()
+                        .map(|e| e.to_offset(super::local_offset_at(e.start_time)))
+                        .collect();
+                    super::select("Which entry?", options)
+                }
+            }
+        }
+    }
+}
+
+/// Ids are MongoDB object ids, everything else must be a date
+pub(super) fn parse_selector(s: &str) -> std::result::Result<EntrySelector, String> {
+    if s.len() == 24 && s.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Ok(EntrySelector::Id(s.to_owned()));
+    }
+    match super::parse_date(s) {
+        Ok(date) => Ok(EntrySelector::Date(date)),
+        Err(_) => Err("Entries are selected by a date like 2023-01-31 or by their 24 character id".into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::{date, datetime};
+    use utilities::mocking;
+
+    use super::*;
+
+    #[test]
+    fn keep_times_copies_shifts_and_breaks() {
+        let existing: TimeEntry = serde_json::from_str(&mocking::read_fixture("entry_closed")).unwrap();
+        let entry = keep_times(&existing).unwrap();
+        assert_eq!(entry.shifts.len(), 1);
+        assert_eq!(entry.shifts[0].start_time, datetime!(2023-01-19 08:22:25 UTC));
+        assert_eq!(entry.shifts[0].end_time, datetime!(2023-01-19 16:22:25 UTC));
+        assert_eq!(entry.breaks.len(), 1);
+        assert_eq!(entry.breaks[0].break_type_id, "break-id-1");
+        assert_eq!(entry.breaks[0].end_time, datetime!(2023-01-19 12:30 UTC));
+    }
+
+    #[test]
+    fn parse_selector() {
+        assert!(matches!(
+            super::parse_selector("2023-01-19"),
+            Ok(EntrySelector::Date(date)) if date == date!(2023 - 01 - 19)
+        ));
+        assert!(matches!(
+            super::parse_selector("63c8f0b1e5a1b2c3d4e5f6a7"),
+            Ok(EntrySelector::Id(id)) if id == "63c8f0b1e5a1b2c3d4e5f6a7"
+        ));
+        assert!(super::parse_selector("2026-13-01").is_err());
+        assert!(super::parse_selector("yesterday").is_err());
+    }
+
+    #[test]
+    fn keep_times_rejects_running_entries() {
+        let existing: TimeEntry = serde_json::from_str(&mocking::read_fixture("time_entry")).unwrap();
+        assert!(matches!(keep_times(&existing), Err(Error::InvalidTime(_))));
+    }
+}
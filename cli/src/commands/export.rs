@@ -0,0 +1,147 @@
+mod entries;
+mod ics;
+mod timewarrior;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use clap::{Args, Subcommand};
+use rippling_api::{time_entries::TimeEntry, Client};
+use time::{Date, Month, OffsetDateTime};
+
+use crate::{persistence, spinner_wrap};
+
+use super::Result;
+
+use self::ics::{Event, EventTime};
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Holidays, leave and optionally time entries as iCalendar events
+    Ics(IcsArgs),
+    /// Time entries with totals as CSV, for spreadsheets
+    Csv(EntriesArgs),
+    /// Time entries with totals as JSON
+    Json(EntriesArgs),
+    /// Time entries as Timewarrior intervals tagged `rippling`, for `timew import`
+    Timewarrior(EntriesArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct IcsArgs {
+    /// First day to export, defaults to the start of the current year
+    #[arg(long, value_parser = super::parse_date)]
+    pub from: Option<Date>,
+    /// Last day to export, defaults to the end of the current year
+    #[arg(long, value_parser = super::parse_date)]
+    pub to: Option<Date>,
+    /// Also export closed time entries
+    #[arg(short, long)]
+    pub entries: bool,
+    /// Write to this file instead of stdout
+    #[arg(short, long)]
+    pub file: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct EntriesArgs {
+    /// First day to export, defaults to the start of the current month
+    #[arg(long, value_parser = super::parse_date)]
+    pub from: Option<Date>,
+    /// Last day to export, defaults to today
+    #[arg(long, value_parser = super::parse_date)]
+    pub to: Option<Date>,
+    /// Write to this file instead of stdout
+    #[arg(short, long)]
+    pub file: Option<PathBuf>,
+}
+
+/// Entrypoint for this module
+pub fn execute(cmd: &Command) -> Result<()> {
+    match cmd {
+        Command::Ics(args) => export_ics(args),
+        Command::Csv(args) => export_entries(args, entries::render_csv),
+        Command::Json(args) => export_entries(args, entries::render_json),
+        Command::Timewarrior(args) => export_timewarrior(args),
+    }
+}
+
+fn export_ics(args: &IcsArgs) -> Result<()> {
+    let year = super::today().year();
+    let from = args
+        .from
+        .unwrap_or(Date::from_calendar_date(year, Month::January, 1).unwrap());
+    let to = args
+        .to
+        .unwrap_or(Date::from_calendar_date(year, Month::December, 31).unwrap());
+    let overlaps = |start: Date, end: Date| start <= to && end >= from;
+
+    let client: Client = persistence::state().into();
+    let (calendar, requests, entries) = spinner_wrap!((
+        client.holiday_calendar()?,
+        client.leave_requests(Some("APPROVED"))?,
+        if args.entries {
+            client.time_entries(super::role(&client)?, from, to)?
+        } else {
+            Vec::new()
+        }
+    ));
+
+    let mut events: Vec<Event> = Vec::new();
+    for holiday in calendar.into_iter().flat_map(|hy| hy.holidays) {
+        if overlaps(holiday.start_date, holiday.end_date) {
+            events.push(Event {
+                uid: ics::holiday_uid(&holiday),
+                summary: holiday.name,
+                time: EventTime::Days(holiday.start_date, holiday.end_date),
+            });
+        }
+    }
+    for request in requests {
+        if overlaps(request.start_date, request.end_date) {
+            events.push(Event {
+                uid: ics::leave_uid(&request),
+                summary: request.leave_type_name,
+                time: EventTime::Days(request.start_date, request.end_date),
+            });
+        }
+    }
+    for entry in entries {
+        if let Some(end_time) = entry.end_time {
+            events.push(Event {
+                uid: ics::entry_uid(&entry),
+                summary: format!("Work ({})", super::format_hours(entry.regular_hours)),
+                time: EventTime::Span(entry.start_time, end_time),
+            });
+        }
+    }
+
+    let out = ics::render(&events, OffsetDateTime::now_utc());
+    write(args.file.as_deref(), &out)
+}
+
+fn export_entries(args: &EntriesArgs, render: fn(&[entries::Row], &entries::Totals) -> String) -> Result<()> {
+    let (rows, totals) = entries::rows(&fetch_entries(args)?);
+    write(args.file.as_deref(), &render(&rows, &totals))
+}
+
+fn export_timewarrior(args: &EntriesArgs) -> Result<()> {
+    write(args.file.as_deref(), &timewarrior::render(&fetch_entries(args)?))
+}
+
+fn fetch_entries(args: &EntriesArgs) -> Result<Vec<TimeEntry>> {
+    let today = super::today();
+    let from = args.from.unwrap_or(today.replace_day(1).unwrap());
+    let to = args.to.unwrap_or(today);
+
+    let client: Client = persistence::state().into();
+    Ok(spinner_wrap!(client.time_entries(super::role(&client)?, from, to))?)
+}
+
+fn write(file: Option<&Path>, out: &str) -> Result<()> {
+    match file {
+        Some(path) => fs::write(path, out)?,
+        None => print!("{out}"),
+    }
+    Ok(())
+}
@@ -0,0 +1,133 @@
+use rippling_api::time_entries::TimeEntry;
+use serde::Serialize;
+
+use super::super::{local_offset_at, FORMAT_DATE, FORMAT_R};
+
+/// One time entry, with times in the local offset
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Row {
+    pub date: String,
+    pub start: String,
+    /// Empty while the entry is still open
+    pub end: String,
+    pub break_minutes: i64,
+    pub regular_hours: f32,
+    pub unpaid_break_hours: f32,
+    pub overtime_hours: f32,
+    pub status: String,
+}
+
+/// Sums over all rows
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct Totals {
+    pub break_minutes: i64,
+    pub regular_hours: f32,
+    pub unpaid_break_hours: f32,
+    pub overtime_hours: f32,
+}
+
+#[derive(Debug, Serialize)]
+struct Report<'a> {
+    entries: &'a [Row],
+    totals: &'a Totals,
+}
+
+impl From<&TimeEntry> for Row {
+    fn from(entry: &TimeEntry) -> Self {
+        // The offset of the day worked, not of today
+        let entry = entry.to_offset(local_offset_at(entry.start_time));
+        let format_time = |time: time::OffsetDateTime| time.format(FORMAT_R).unwrap();
+        Self {
+            date: entry.start_date.format(FORMAT_DATE).unwrap(),
+            start: format_time(entry.start_time),
+            end: entry.end_time.map(format_time).unwrap_or_default(),
+            break_minutes: entry
+                .breaks
+                .iter()
+                .filter_map(|b| b.duration())
+                .sum::<time::Duration>()
+                .whole_minutes(),
+            regular_hours: round(entry.regular_hours),
+            unpaid_break_hours: round(entry.unpaid_break_hours),
+            overtime_hours: round(entry.overtime_hours),
+            status: entry.status,
+        }
+    }
+}
+
+pub fn rows(entries: &[TimeEntry]) -> (Vec<Row>, Totals) {
+    let rows: Vec<Row> = entries.iter().map(Row::from).collect();
+    let mut totals = Totals::default();
+    for row in &rows {
+        totals.break_minutes += row.break_minutes;
+        totals.regular_hours += row.regular_hours;
+        totals.unpaid_break_hours += row.unpaid_break_hours;
+        totals.overtime_hours += row.overtime_hours;
+    }
+    totals.regular_hours = round(totals.regular_hours);
+    totals.unpaid_break_hours = round(totals.unpaid_break_hours);
+    totals.overtime_hours = round(totals.overtime_hours);
+    (rows, totals)
+}
+
+/// CSV with a header, one line per row and a last line with the totals
+pub fn render_csv(rows: &[Row], totals: &Totals) -> String {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    for row in rows {
+        writer.serialize(row).unwrap();
+    }
+    let total = Row {
+        date: "Total".into(),
+        start: String::new(),
+        end: String::new(),
+        break_minutes: totals.break_minutes,
+        regular_hours: totals.regular_hours,
+        unpaid_break_hours: totals.unpaid_break_hours,
+        overtime_hours: totals.overtime_hours,
+        status: String::new(),
+    };
+    writer.serialize(total).unwrap();
+    String::from_utf8(writer.into_inner().unwrap()).unwrap()
+}
+
+/// JSON object with the `entries` and their `totals`
+pub fn render_json(rows: &[Row], totals: &Totals) -> String {
+    serde_json::to_string_pretty(&Report { entries: rows, totals }).unwrap() + "\n"
+}
+
+/// Hours with two decimals, as shown in a spreadsheet
+fn round(hours: f32) -> f32 {
+    (hours * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use utilities::mocking;
+
+    use super::*;
+
+    fn entries() -> Vec<TimeEntry> {
+        let entry: TimeEntry = serde_json::from_str(&mocking::read_fixture("entry_closed")).unwrap();
+        vec![entry.clone(), entry]
+    }
+
+    #[test]
+    fn it_renders_csv_with_totals() {
+        let (rows, totals) = rows(&entries());
+        assert_eq!(
+            render_csv(&rows, &totals),
+            "date,start,end,break_minutes,regular_hours,unpaid_break_hours,overtime_hours,status\n\
+             2023-01-19,09:22,17:22,30,7.5,0.5,0.0,APPROVED\n\
+             2023-01-19,09:22,17:22,30,7.5,0.5,0.0,APPROVED\n\
+             Total,,,60,15.0,1.0,0.0,\n"
+        );
+    }
+
+    #[test]
+    fn it_renders_json_with_totals() {
+        let (rows, totals) = rows(&entries()[..1]);
+        let value: serde_json::Value = serde_json::from_str(&render_json(&rows, &totals)).unwrap();
+        assert_eq!(value["entries"][0]["end"], "17:22");
+        assert_eq!(value["totals"]["regular_hours"], 7.5);
+    }
+}
@@ -0,0 +1,224 @@
+use rippling_api::{
+    pto::{Holiday, LeaveRequest},
+    time_entries::TimeEntry,
+};
+use time::{macros::format_description, Date, Duration, OffsetDateTime, UtcOffset};
+
+const DATE_FMT: &[time::format_description::FormatItem] = format_description!("[year][month][day]");
+const DATETIME_FMT: &[time::format_description::FormatItem] =
+    format_description!("[year][month][day]T[hour][minute][second]Z");
+
+/// Longest line allowed by RFC 5545, in octets, excluding the line break
+const MAX_LINE: usize = 75;
+
+#[derive(Clone, Debug)]
+pub enum EventTime {
+    /// All-day events, both days inclusive
+    Days(Date, Date),
+    Span(OffsetDateTime, OffsetDateTime),
+}
+
+#[derive(Clone, Debug)]
+pub struct Event {
+    /// Identifies the event across exports, so calendars update instead of duplicating it
+    pub uid: String,
+    pub summary: String,
+    pub time: EventTime,
+}
+
+/// Renders a VCALENDAR with one VEVENT per event
+pub fn render(events: &[Event], stamp: OffsetDateTime) -> String {
+    let mut out = String::new();
+    line(&mut out, "BEGIN:VCALENDAR");
+    line(&mut out, "VERSION:2.0");
+    line(&mut out, "PRODID:-//rippling-cli//EN");
+    line(&mut out, "CALSCALE:GREGORIAN");
+    for event in events {
+        line(&mut out, "BEGIN:VEVENT");
+        line(&mut out, &format!("UID:{}", escape(&event.uid)));
+        line(&mut out, &format!("DTSTAMP:{}", format_utc(stamp)));
+        match event.time {
+            EventTime::Days(start, end) => {
+                line(
+                    &mut out,
+                    &format!("DTSTART;VALUE=DATE:{}", start.format(DATE_FMT).unwrap()),
+                );
+                let end = end + Duration::days(1);
+                line(&mut out, &format!("DTEND;VALUE=DATE:{}", end.format(DATE_FMT).unwrap()));
+                line(&mut out, "TRANSP:TRANSPARENT");
+            }
+            EventTime::Span(start, end) => {
+                line(&mut out, &format!("DTSTART:{}", format_utc(start)));
+                line(&mut out, &format!("DTEND:{}", format_utc(end)));
+            }
+        }
+        line(&mut out, &format!("SUMMARY:{}", escape(&event.summary)));
+        line(&mut out, "END:VEVENT");
+    }
+    line(&mut out, "END:VCALENDAR");
+    out
+}
+
+fn format_utc(time: OffsetDateTime) -> String {
+    time.to_offset(UtcOffset::UTC).format(DATETIME_FMT).unwrap()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\n")
+        .replace(['\r', '\n'], "\\n")
+}
+
+/// Writes a content line, folding it into several lines where it is too long
+fn line(out: &mut String, content: &str) {
+    let mut len = 0;
+    for c in content.chars() {
+        if len + c.len_utf8() > MAX_LINE {
+            out.push_str("\r\n ");
+            len = 1;
+        }
+        out.push(c);
+        len += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+/// Holidays have no id, the same name on the same day is the same holiday
+pub fn holiday_uid(holiday: &Holiday) -> String {
+    format!("holiday-{}-{}@rippling-cli", holiday.start_date, slug(&holiday.name))
+}
+
+pub fn leave_uid(request: &LeaveRequest) -> String {
+    format!("leave-{}@rippling-cli", request.id)
+}
+
+pub fn entry_uid(entry: &TimeEntry) -> String {
+    format!("entry-{}@rippling-cli", entry.id)
+}
+
+/// Turns a name into something usable within an UID
+///
+/// Names without any letters or digits are replaced by a hash, so they still tell holidays apart.
+fn slug(name: &str) -> String {
+    let mut slug = String::new();
+    for c in name.chars() {
+        if c.is_alphanumeric() {
+            slug.extend(c.to_lowercase());
+        } else if matches!(c, ' ' | '-' | '_') {
+            slug.push('-');
+        }
+    }
+    if slug.chars().any(char::is_alphanumeric) {
+        slug
+    } else {
+        format!("{:016x}", fnv1a(name.as_bytes()))
+    }
+}
+
+/// FNV-1a, unlike the hasher of the standard library it is guaranteed to stay the same
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::{date, datetime};
+    use utilities::mocking;
+
+    use super::*;
+
+    fn holiday(name: &str, date: Date) -> Holiday {
+        Holiday {
+            name: name.into(),
+            kind: "PUBLIC".into(),
+            start_date: date,
+            end_date: date,
+            count_as_overtime: false,
+        }
+    }
+
+    #[test]
+    fn it_renders_events() {
+        let events = [
+            Event {
+                uid: holiday_uid(&holiday("Christmas Day", date!(2026 - 12 - 25))),
+                summary: "Christmas Day".into(),
+                time: EventTime::Days(date!(2026 - 12 - 25), date!(2026 - 12 - 25)),
+            },
+            Event {
+                uid: "entry-some-entry-id@rippling-cli".into(),
+                summary: "Work; 7:30".into(),
+                time: EventTime::Span(datetime!(2023-01-19 09:22:25 +1), datetime!(2023-01-19 17:22:25 +1)),
+            },
+        ];
+        let out = render(&events, datetime!(2026-10-17 12:00 UTC));
+        let lines: Vec<&str> = out.split("\r\n").collect();
+        assert_eq!(
+            lines[4..7],
+            [
+                "BEGIN:VEVENT",
+                "UID:holiday-2026-12-25-christmas-day@rippling-cli",
+                "DTSTAMP:20261017T120000Z"
+            ]
+        );
+        assert_eq!(
+            lines[7..9],
+            ["DTSTART;VALUE=DATE:20261225", "DTEND;VALUE=DATE:20261226"]
+        );
+        assert!(lines.contains(&"DTSTART:20230119T082225Z"));
+        assert!(lines.contains(&"SUMMARY:Work\\; 7:30"));
+        assert_eq!(lines[lines.len() - 2], "END:VCALENDAR");
+    }
+
+    #[test]
+    fn it_folds_long_lines() {
+        let mut out = String::new();
+        line(&mut out, &"x".repeat(100));
+        assert_eq!(out, format!("{}\r\n {}\r\n", "x".repeat(75), "x".repeat(25)));
+    }
+
+    #[test]
+    fn holiday_uids_are_stable_and_unique() {
+        let christmas = holiday("Christmas Day", date!(2026 - 12 - 25));
+        assert_eq!(holiday_uid(&christmas), "holiday-2026-12-25-christmas-day@rippling-cli");
+        assert_eq!(holiday_uid(&christmas), holiday_uid(&christmas.clone()));
+        assert_ne!(
+            holiday_uid(&christmas),
+            holiday_uid(&holiday("Christmas Day", date!(2027 - 12 - 25)))
+        );
+        assert_ne!(
+            holiday_uid(&christmas),
+            holiday_uid(&holiday("Christmas Eve", date!(2026 - 12 - 25)))
+        );
+    }
+
+    #[test]
+    fn leave_and_entry_uids_use_their_ids() {
+        let request: LeaveRequest = serde_json::from_str(&mocking::read_fixture("leave_request")).unwrap();
+        assert_eq!(leave_uid(&request), "leave-pto-request-3@rippling-cli");
+        let entry: TimeEntry = serde_json::from_str(&mocking::read_fixture("entry_closed")).unwrap();
+        assert_eq!(entry_uid(&entry), "entry-some-entry-id@rippling-cli");
+    }
+
+    #[test]
+    fn slug() {
+        assert_eq!(super::slug("New Year's Day"), "new-years-day");
+        assert_eq!(super::slug("Fête Nationale"), "fête-nationale");
+        assert_eq!(super::slug("元日"), "元日");
+        assert_eq!(super::slug("★"), super::slug("★"));
+        assert_ne!(super::slug("★"), super::slug("☆"));
+        assert_eq!(super::slug("★").len(), 16);
+    }
+
+    #[test]
+    fn escape() {
+        assert_eq!(
+            super::escape("Trip; Hamburg,\r\nBerlin\rBremen"),
+            "Trip\\; Hamburg\\,\\nBerlin\\nBremen"
+        );
+    }
+}
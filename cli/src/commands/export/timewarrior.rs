@@ -0,0 +1,82 @@
+use rippling_api::time_entries::TimeEntry;
+use serde::Serialize;
+use time::{macros::format_description, OffsetDateTime, UtcOffset};
+
+use super::super::import::timewarrior::TAG;
+
+const DATETIME_FMT: &[time::format_description::FormatItem] =
+    format_description!("[year][month][day]T[hour][minute][second]Z");
+
+/// An interval as read by `timew import`
+#[derive(Debug, Serialize)]
+struct Interval {
+    start: String,
+    end: String,
+    tags: [&'static str; 1],
+    annotation: String,
+}
+
+/// JSON for `timew import`, with one interval per stretch of work between breaks of closed entries
+pub fn render(entries: &[TimeEntry]) -> String {
+    let mut intervals: Vec<Interval> = Vec::new();
+    for entry in entries {
+        let Some(end_time) = entry.end_time else { continue };
+        let mut breaks: Vec<_> = entry
+            .breaks
+            .iter()
+            .filter_map(|b| Some((b.start_time, b.end_time?)))
+            .collect();
+        breaks.sort_by_key(|(start, _)| *start);
+
+        let mut bounds = vec![entry.start_time];
+        for (start, end) in breaks {
+            bounds.push(start);
+            bounds.push(end);
+        }
+        bounds.push(end_time);
+        for pair in bounds.chunks_exact(2).filter(|pair| pair[0] < pair[1]) {
+            intervals.push(Interval {
+                start: format_utc(pair[0]),
+                end: format_utc(pair[1]),
+                tags: [TAG],
+                annotation: format!("rippling entry {}", entry.id),
+            });
+        }
+    }
+    serde_json::to_string_pretty(&intervals).unwrap() + "\n"
+}
+
+fn format_utc(time: OffsetDateTime) -> String {
+    time.to_offset(UtcOffset::UTC).format(DATETIME_FMT).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use utilities::mocking;
+
+    use super::*;
+
+    #[test]
+    fn it_renders_work_between_breaks() {
+        let entry: TimeEntry = serde_json::from_str(&mocking::read_fixture("entry_closed")).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&render(&[entry])).unwrap();
+        assert_eq!(
+            value,
+            json!([
+                {
+                    "start": "20230119T082225Z",
+                    "end": "20230119T120000Z",
+                    "tags": ["rippling"],
+                    "annotation": "rippling entry some-entry-id"
+                },
+                {
+                    "start": "20230119T123000Z",
+                    "end": "20230119T162225Z",
+                    "tags": ["rippling"],
+                    "annotation": "rippling entry some-entry-id"
+                }
+            ])
+        );
+    }
+}
@@ -0,0 +1,375 @@
+pub(super) mod timewarrior;
+mod toggl;
+
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::result::Result as StdResult;
+
+use clap::{Args, Subcommand};
+use rippling_api::{
+    time_entries::{NewTimeEntry, TimeEntry},
+    Client,
+};
+use serde::Serialize;
+use time::{Date, Duration};
+
+use crate::{persistence, spinner_wrap};
+
+use super::manual_entry::{self, TimeRange};
+use super::pto::{CheckOutcome, DaysOff};
+use super::{local_time_format, output, DateRange, Result};
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Manual entries from a CSV file with the columns date,ranges[,note]
+    Csv(CsvArgs),
+    /// Intervals from Timewarrior data files or `timew export` JSON
+    Timewarrior(TimewarriorArgs),
+    /// Detailed CSV reports of Toggl Track or Clockify
+    #[clap(alias = "clockify")]
+    Toggl(TogglArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct CsvArgs {
+    /// For example a line `2026-10-05,"8:30-12,12:30-17",Trip to Hamburg`
+    pub file: PathBuf,
+    /// Bypass prompt with a yes answer
+    #[arg(short, long)]
+    pub yes: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct TimewarriorArgs {
+    /// Data files or `timew export` output, defaults to all files in the Timewarrior data folder
+    pub files: Vec<PathBuf>,
+    /// Days to import, for example 2026-10-01..2026-10-31
+    #[arg(long, value_parser = super::parse_date_range)]
+    pub range: DateRange,
+    /// Before submitting check for overlap with holidays, weekends or PTO
+    #[arg(short, long)]
+    pub check: bool,
+    /// Bypass prompt with a yes answer
+    #[arg(short, long)]
+    pub yes: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct TogglArgs {
+    pub file: PathBuf,
+    /// Only import these days, for example 2026-10-01..2026-10-31
+    #[arg(long, value_parser = super::parse_date_range)]
+    pub range: Option<DateRange>,
+    /// Shortest gap between two rows in minutes which becomes a break, shorter gaps count as work
+    #[arg(long, default_value_t = 15)]
+    pub min_break: u16,
+    /// How to read dates with slashes, required when both readings are possible
+    #[arg(long, value_enum)]
+    pub date_format: Option<toggl::DateOrder>,
+    /// Before submitting check for overlap with holidays, weekends or PTO
+    #[arg(short, long)]
+    pub check: bool,
+    /// Bypass prompt with a yes answer
+    #[arg(short, long)]
+    pub yes: bool,
+}
+
+/// A line of the imported file and what will be done with it
+struct Row {
+    line: u64,
+    date: String,
+    ranges: String,
+    note: String,
+    /// The entry to submit, or why the line is skipped
+    entry: StdResult<NewTimeEntry, String>,
+}
+
+/// Result of `import csv`, as printed with `--output json|yaml`
+#[derive(Debug, Serialize)]
+struct ImportReport {
+    event: &'static str,
+    lines: Vec<ImportedLine>,
+}
+
+/// What happened to a line of the imported file
+#[derive(Debug, Serialize)]
+struct ImportedLine {
+    line: u64,
+    date: String,
+    /// One of `added`, `skipped` or `failed`
+    status: &'static str,
+    /// Why the line was skipped or failed
+    reason: Option<String>,
+    entry: Option<output::Entry>,
+}
+
+/// Entrypoint for this module
+pub fn execute(cmd: &Command) -> Result<()> {
+    let client: Client = persistence::state().into();
+    match cmd {
+        Command::Csv(args) => import_csv(&client, args),
+        Command::Timewarrior(args) => import_timewarrior(&client, args),
+        Command::Toggl(args) => import_toggl(&client, args),
+    }
+}
+
+fn import_csv(client: &Client, args: &CsvArgs) -> Result<()> {
+    let content = fs::read(&args.file)?;
+    let (break_policy, days_off) = spinner_wrap!((manual_entry::fetch_break_policy(client)?, DaysOff::fetch(client)?));
+    let btype = break_policy
+        .manual_break_type()
+        .ok_or(super::Error::NoManualBreakType)?;
+
+    let rows: Vec<Row> = read_csv(&content)
+        .into_iter()
+        .map(|(line, fields)| {
+            let [date, ranges, note] = fields.clone().unwrap_or_default();
+            let entry = fields.and_then(|[date, ranges, note]| {
+                let (day, ranges) = parse_day(&date, &ranges)?;
+                match days_off.check(day) {
+                    CheckOutcome::WorkingDay => {}
+                    outcome => return Err(outcome.to_string()),
+                }
+                let mut entry = manual_entry::build_entry(day, &ranges, &btype.id);
+                entry.notes = Some(note).filter(|n| !n.is_empty());
+                Ok(entry)
+            });
+            Row { line, date, ranges, note, entry }
+        })
+        .collect();
+
+    let valid = rows.iter().filter(|r| r.entry.is_ok()).count();
+    if valid == 0 {
+        let report = ImportReport { event: "import", lines: imported_lines(&rows, &[]) };
+        output::print(&report, || format!("{}Nothing to import", render_summary(&rows)));
+        return Ok(());
+    }
+    output::note(render_summary(&rows).trim_end());
+    let question = format!("Submit {valid} of {} entries?", rows.len());
+    if !(args.yes || super::confirm(&question)?) {
+        return Ok(());
+    }
+
+    let mut created = Vec::new();
+    for row in &rows {
+        let Ok(entry) = &row.entry else { continue };
+        created.push(spinner_wrap!(client.create_time_entry(entry)).map_err(|e| e.to_string()));
+    }
+    let report = ImportReport { event: "import", lines: imported_lines(&rows, &created) };
+    output::print(&report, || {
+        let mut text = String::new();
+        for (row, created) in rows.iter().filter(|r| r.entry.is_ok()).zip(&created) {
+            match created {
+                Ok(created) => writeln!(
+                    text,
+                    "Line {} ({}): added entry from {} to {}",
+                    row.line,
+                    row.date,
+                    local_time_format(created.start_time),
+                    created.end_time.map(local_time_format).unwrap_or("-".into())
+                ),
+                Err(e) => writeln!(text, "Line {} ({}): failed, {e}", row.line, row.date),
+            }
+            .unwrap();
+        }
+        text
+    });
+    Ok(())
+}
+
+/// What happened to each row, given the outcomes of submitting the valid rows in order
+fn imported_lines(rows: &[Row], created: &[StdResult<TimeEntry, String>]) -> Vec<ImportedLine> {
+    let mut created = created.iter();
+    rows.iter()
+        .map(|row| {
+            let outcome = if row.entry.is_ok() { created.next() } else { None };
+            let (status, reason, entry) = match (&row.entry, outcome) {
+                (Err(reason), _) => ("skipped", Some(reason.clone()), None),
+                (Ok(_), Some(Ok(created))) => ("added", None, Some(output::Entry::from(created))),
+                (Ok(_), Some(Err(reason))) => ("failed", Some(reason.clone()), None),
+                (Ok(_), None) => ("failed", Some("not submitted".into()), None),
+            };
+            ImportedLine { line: row.line, date: row.date.clone(), status, reason, entry }
+        })
+        .collect()
+}
+
+fn import_timewarrior(client: &Client, args: &TimewarriorArgs) -> Result<()> {
+    let contents = if args.files.is_empty() {
+        timewarrior::read_data_dir(&timewarrior::data_dir()?)?
+    } else {
+        args.files.iter().map(fs::read_to_string).collect::<io::Result<_>>()?
+    };
+    let mut intervals = Vec::new();
+    for content in &contents {
+        intervals.extend(timewarrior::intervals(content).map_err(super::Error::InvalidFile)?);
+    }
+    let days = manual_entry::ranges_by_day(intervals, args.range, Duration::ZERO);
+    manual_entry::submit_days(client, days, args.check, args.yes)
+}
+
+fn import_toggl(client: &Client, args: &TogglArgs) -> Result<()> {
+    let intervals =
+        toggl::intervals(&fs::read_to_string(&args.file)?, args.date_format).map_err(super::Error::InvalidFile)?;
+    let range = args.range.unwrap_or(DateRange { from: Date::MIN, to: Date::MAX });
+    let min_gap = Duration::minutes(i64::from(args.min_break));
+    let days = manual_entry::ranges_by_day(intervals, range, min_gap);
+    manual_entry::submit_days(client, days, args.check, args.yes)
+}
+
+/// Line number and the fields date, ranges and note of every record, skipping a header
+///
+/// Records the CSV reader rejects are kept with the reason, so they show up in the summary.
+fn read_csv(content: &[u8]) -> Vec<(u64, StdResult<[String; 3], String>)> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(content);
+    reader
+        .records()
+        .filter(|record| {
+            !record
+                .as_ref()
+                .is_ok_and(|r| r.get(0).is_some_and(|f| f.eq_ignore_ascii_case("date")))
+        })
+        .map(|record| match record {
+            Ok(record) => {
+                let line = record.position().map_or(0, |p| p.line());
+                let field = |i| record.get(i).unwrap_or_default().to_owned();
+                (line, Ok([field(0), field(1), field(2)]))
+            }
+            Err(e) => {
+                let line = e.position().map_or(0, |p| p.line());
+                (line, Err(e.to_string()))
+            }
+        })
+        .collect()
+}
+
+fn parse_day(date: &str, ranges: &str) -> StdResult<(Date, Vec<TimeRange>), String> {
+    let date = super::parse_date(date)?;
+    let ranges = ranges
+        .split(',')
+        .map(|r| manual_entry::parse_input_shifts(r.trim()))
+        .collect::<StdResult<Vec<_>, _>>()?;
+    manual_entry::check_ranges(&ranges)?;
+    Ok((date, ranges))
+}
+
+fn render_summary(rows: &[Row]) -> String {
+    let mut out = String::new();
+    writeln!(
+        out,
+        "{:>4} {:<10} {:<24} {:<20} Status",
+        "Line", "Date", "Shifts", "Note"
+    )
+    .unwrap();
+    for row in rows {
+        let status = match &row.entry {
+            Ok(_) => "ok",
+            Err(reason) => reason,
+        };
+        let note: String = row.note.chars().take(20).collect();
+        writeln!(
+            out,
+            "{:>4} {:<10} {:<24} {:<20} {status}",
+            row.line, row.date, row.ranges, note
+        )
+        .unwrap();
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use time::macros::date;
+    use utilities::mocking;
+
+    use super::*;
+
+    #[test]
+    fn it_reads_rows() {
+        let content = "date,ranges,note\n2026-10-05,\"8:30-12, 12:30-17\",Trip to Hamburg\n2026-10-06,9-17\n";
+        let rows = read_csv(content.as_bytes());
+        assert_eq!(rows.len(), 2);
+        assert_eq!(
+            rows[0],
+            (
+                2,
+                Ok([
+                    "2026-10-05".into(),
+                    "8:30-12, 12:30-17".into(),
+                    "Trip to Hamburg".into()
+                ])
+            )
+        );
+        assert_eq!(rows[1], (3, Ok(["2026-10-06".into(), "9-17".into(), String::new()])));
+    }
+
+    #[test]
+    fn it_keeps_unreadable_rows() {
+        let content = b"2026-10-05,9-17,Caf\xe9\n2026-10-06,9-17\n";
+        let rows = read_csv(content);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].0, 1);
+        assert!(rows[0].1.is_err());
+        assert!(rows[1].1.is_ok());
+    }
+
+    #[test]
+    fn it_serializes_an_import_report() {
+        let (date, ranges) = parse_day("2023-01-19", "9-17").unwrap();
+        let entry = manual_entry::build_entry(date, &ranges, "some-break-type-id");
+        let row = |line: u64, entry| Row {
+            line,
+            date: "2023-01-19".into(),
+            ranges: "9-17".into(),
+            note: String::new(),
+            entry,
+        };
+        let rows = [
+            row(1, Err("It is a holiday (New Year's Day)".into())),
+            row(2, Ok(entry.clone())),
+            row(3, Ok(entry)),
+        ];
+        let created: TimeEntry = serde_json::from_str(&mocking::read_fixture("entry_closed")).unwrap();
+        let report = ImportReport {
+            event: "import",
+            lines: imported_lines(&rows, &[Ok(created), Err("Conflicting entry".into())]),
+        };
+        let value = serde_json::to_value(&report).unwrap();
+        assert_eq!(value["event"], "import");
+        assert_eq!(
+            value["lines"][0],
+            json!({
+                "line": 1,
+                "date": "2023-01-19",
+                "status": "skipped",
+                "reason": "It is a holiday (New Year's Day)",
+                "entry": null
+            })
+        );
+        assert_eq!(value["lines"][1]["status"], "added");
+        assert_eq!(value["lines"][1]["reason"], json!(null));
+        assert_eq!(value["lines"][1]["entry"]["id"], "some-entry-id");
+        assert_eq!(
+            value["lines"][2],
+            json!({"line": 3, "date": "2023-01-19", "status": "failed", "reason": "Conflicting entry", "entry": null})
+        );
+    }
+
+    #[test]
+    fn it_validates_days() {
+        let (date, ranges) = parse_day("2026-10-05", "8:30-12, 12:30-17").unwrap();
+        assert_eq!(date, date!(2026 - 10 - 05));
+        assert_eq!(ranges.len(), 2);
+        assert!(parse_day("05.10.2026", "8:30-17").is_err());
+        assert!(parse_day("2026-10-05", "8:30").is_err());
+        assert!(parse_day("2026-10-05", "17-8:30").is_err());
+        assert!(parse_day("2026-10-05", "8-13,12-17").is_err());
+    }
+}
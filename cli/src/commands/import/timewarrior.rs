@@ -0,0 +1,193 @@
+use std::{
+    env,
+    ffi::OsString,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+use time::{macros::format_description, PrimitiveDateTime};
+
+use super::super::local_datetime;
+
+const DATETIME_FMT: &[time::format_description::FormatItem] =
+    format_description!("[year][month][day]T[hour][minute][second]Z");
+
+/// Tag of intervals which were exported from rippling, they are never imported again
+pub const TAG: &str = "rippling";
+
+/// An interval as written by `timew export`
+#[derive(Debug, Deserialize)]
+struct Interval {
+    start: String,
+    end: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+/// The data folder of the first existing database, `$TIMEWARRIORDB`, `~/.timewarrior` or
+/// `$XDG_DATA_HOME/timewarrior` (by default `~/.local/share/timewarrior`)
+pub fn data_dir() -> io::Result<PathBuf> {
+    let home = directories::BaseDirs::new().map(|dirs| dirs.home_dir().to_owned());
+    let candidates = databases(env::var_os("TIMEWARRIORDB"), env::var_os("XDG_DATA_HOME"), home);
+    first_data_dir(&candidates)
+}
+
+/// Where Timewarrior looks for its database, in order
+fn databases(timewarriordb: Option<OsString>, xdg_data_home: Option<OsString>, home: Option<PathBuf>) -> Vec<PathBuf> {
+    if let Some(db) = timewarriordb {
+        return vec![PathBuf::from(db)];
+    }
+    let xdg_data_home = xdg_data_home
+        .map(PathBuf::from)
+        .or_else(|| home.as_ref().map(|home| home.join(".local/share")));
+    home.map(|home| home.join(".timewarrior"))
+        .into_iter()
+        .chain(xdg_data_home.map(|dir| dir.join("timewarrior")))
+        .collect()
+}
+
+fn first_data_dir(databases: &[PathBuf]) -> io::Result<PathBuf> {
+    let dirs: Vec<PathBuf> = databases.iter().map(|db| db.join("data")).collect();
+    dirs.iter().find(|dir| dir.is_dir()).cloned().ok_or_else(|| {
+        let tried: Vec<String> = dirs.iter().map(|dir| dir.display().to_string()).collect();
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!(
+                "No Timewarrior data folder found in {}, give the files or set TIMEWARRIORDB",
+                tried.join(", ")
+            ),
+        )
+    })
+}
+
+/// Contents of all monthly data files in `dir`
+pub fn read_data_dir(dir: &Path) -> io::Result<Vec<String>> {
+    let mut contents = Vec::new();
+    for file in fs::read_dir(dir)? {
+        let path = file?.path();
+        if path.extension().is_some_and(|ext| ext == "data") {
+            contents.push(fs::read_to_string(path)?);
+        }
+    }
+    Ok(contents)
+}
+
+/// Closed intervals in local time, from either `timew export` JSON or a data file
+pub fn intervals(content: &str) -> Result<Vec<(PrimitiveDateTime, PrimitiveDateTime)>, String> {
+    let intervals: Vec<Interval> = if content.trim_start().starts_with('[') {
+        serde_json::from_str(content).map_err(|e| e.to_string())?
+    } else {
+        content.lines().filter_map(parse_line).collect()
+    };
+    let mut closed = Vec::new();
+    for interval in intervals {
+        let Some(end) = interval.end else { continue };
+        if !interval.tags.iter().any(|tag| tag == TAG) {
+            closed.push((parse_datetime(&interval.start)?, parse_datetime(&end)?));
+        }
+    }
+    Ok(closed)
+}
+
+/// Lines look like `inc 20261005T063000Z - 20261005T100000Z # tag "other tag"`
+fn parse_line(line: &str) -> Option<Interval> {
+    let (times, tags) = line.split_once(" # ").unwrap_or((line, ""));
+    let mut times = times.strip_prefix("inc ")?.split(" - ");
+    let start = times.next()?.trim().to_owned();
+    let end = times.next().map(|end| end.trim().to_owned());
+    Some(Interval { start, end, tags: parse_tags(tags) })
+}
+
+fn parse_tags(s: &str) -> Vec<String> {
+    let mut tags = Vec::new();
+    let mut rest = s.trim();
+    while !rest.is_empty() {
+        let (tag, tail) = match rest.strip_prefix('"') {
+            Some(quoted) => quoted.split_once('"').unwrap_or((quoted, "")),
+            None => rest.split_once(' ').unwrap_or((rest, "")),
+        };
+        tags.push(tag.to_owned());
+        rest = tail.trim_start();
+    }
+    tags
+}
+
+fn parse_datetime(s: &str) -> Result<PrimitiveDateTime, String> {
+    let utc = PrimitiveDateTime::parse(s, DATETIME_FMT).map_err(|_| format!("Invalid time {s}"))?;
+    Ok(local_datetime(utc.assume_utc()))
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use super::*;
+
+    #[test]
+    fn it_looks_for_the_database_like_timewarrior() {
+        let home = Some(PathBuf::from("/home/me"));
+        assert_eq!(
+            databases(None, None, home.clone()),
+            [
+                PathBuf::from("/home/me/.timewarrior"),
+                PathBuf::from("/home/me/.local/share/timewarrior")
+            ]
+        );
+        assert_eq!(
+            databases(None, Some("/data".into()), home.clone()),
+            [
+                PathBuf::from("/home/me/.timewarrior"),
+                PathBuf::from("/data/timewarrior")
+            ]
+        );
+        assert_eq!(databases(Some("/db".into()), None, home), [PathBuf::from("/db")]);
+    }
+
+    #[test]
+    fn it_names_the_folders_it_tried() {
+        let error = first_data_dir(&[PathBuf::from("/nowhere/a"), PathBuf::from("/nowhere/b")]).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::NotFound);
+        assert_eq!(
+            error.to_string(),
+            "No Timewarrior data folder found in /nowhere/a/data, /nowhere/b/data, give the files or set TIMEWARRIORDB"
+        );
+    }
+
+    #[test]
+    fn it_reads_data_files() {
+        let content = "inc 20261005T063000Z - 20261005T100000Z # project \"client work\"\n\
+                       inc 20261005T103000Z - 20261005T150000Z # rippling\n\
+                       inc 20261006T070000Z\n";
+        assert_eq!(
+            intervals(content),
+            Ok(vec![(datetime!(2026-10-05 08:30), datetime!(2026-10-05 12:00))])
+        );
+    }
+
+    #[test]
+    fn it_reads_exported_json() {
+        let content = r#"[
+            {"id": 2, "start": "20261005T063000Z", "end": "20261005T100000Z", "tags": ["project"]},
+            {"id": 1, "start": "20261005T103000Z"}
+        ]"#;
+        assert_eq!(
+            intervals(content),
+            Ok(vec![(datetime!(2026-10-05 08:30), datetime!(2026-10-05 12:00))])
+        );
+    }
+
+    #[test]
+    fn it_rejects_malformed_json() {
+        assert!(intervals(r#"[{"start": "20261005T063000Z""#).is_err());
+        assert_eq!(
+            intervals(r#"[{"start": "2026-10-05 08:30", "end": "20261005T100000Z"}]"#),
+            Err("Invalid time 2026-10-05 08:30".into())
+        );
+    }
+
+    #[test]
+    fn parse_tags() {
+        assert_eq!(super::parse_tags("a \"b c\" d"), vec!["a", "b c", "d"]);
+    }
+}
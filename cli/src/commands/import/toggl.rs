@@ -0,0 +1,148 @@
+use std::result::Result as StdResult;
+
+use clap::ValueEnum;
+use time::{format_description::FormatItem, macros::format_description, Date, PrimitiveDateTime, Time};
+
+/// Unambiguous date formats used by Toggl Track and, depending on the workspace settings, Clockify
+const DATE_FMTS: [&[FormatItem]; 2] = [
+    format_description!("[year]-[month]-[day]"),
+    format_description!("[day].[month].[year]"),
+];
+const MONTH_FIRST_FMT: &[FormatItem] = format_description!("[month]/[day]/[year]");
+const DAY_FIRST_FMT: &[FormatItem] = format_description!("[day]/[month]/[year]");
+const TIME_FMTS: [&[FormatItem]; 4] = [
+    format_description!("[hour]:[minute]:[second]"),
+    format_description!("[hour]:[minute]"),
+    format_description!("[hour repr:12]:[minute]:[second] [period]"),
+    format_description!("[hour repr:12]:[minute] [period]"),
+];
+
+/// How to read dates like 05/10/2026
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum DateOrder {
+    /// Month first, 05/10/2026 is May 10th
+    Mdy,
+    /// Day first, 05/10/2026 is October 5th
+    Dmy,
+}
+
+/// Start and end of every row of a detailed report, in local time
+///
+/// Dates with slashes which could be read both ways are rejected unless `order` is given.
+pub fn intervals(
+    content: &str,
+    order: Option<DateOrder>,
+) -> StdResult<Vec<(PrimitiveDateTime, PrimitiveDateTime)>, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(content.as_bytes());
+    let headers = reader.headers().map_err(|e| e.to_string())?.clone();
+    let column = |name: &str| {
+        headers
+            .iter()
+            .position(|h| h.trim_start_matches('\u{feff}').eq_ignore_ascii_case(name))
+            .ok_or_else(|| format!("Column {name} is missing"))
+    };
+    let columns = [
+        column("Start date")?,
+        column("Start time")?,
+        column("End date")?,
+        column("End time")?,
+    ];
+
+    let mut intervals = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(|e| e.to_string())?;
+        let [start_date, start_time, end_date, end_time] = columns.map(|i| record.get(i).unwrap_or_default());
+        let line = record.position().map_or(0, |p| p.line());
+        let start = parse_datetime(start_date, start_time, order)
+            .map_err(|reason| format!("Invalid start in line {line}: {reason}"))?;
+        let end = parse_datetime(end_date, end_time, order)
+            .map_err(|reason| format!("Invalid end in line {line}: {reason}"))?;
+        intervals.push((start, end));
+    }
+    Ok(intervals)
+}
+
+fn parse_datetime(date: &str, time: &str, order: Option<DateOrder>) -> StdResult<PrimitiveDateTime, String> {
+    let date = parse_date(date, order)?;
+    let time = TIME_FMTS
+        .iter()
+        .find_map(|fmt| Time::parse(time, fmt).ok())
+        .ok_or_else(|| format!("unknown time format {time}"))?;
+    Ok(PrimitiveDateTime::new(date, time))
+}
+
+fn parse_date(date: &str, order: Option<DateOrder>) -> StdResult<Date, String> {
+    if let Some(parsed) = DATE_FMTS.iter().find_map(|fmt| Date::parse(date, fmt).ok()) {
+        return Ok(parsed);
+    }
+    let month_first = Date::parse(date, MONTH_FIRST_FMT).ok();
+    let day_first = Date::parse(date, DAY_FIRST_FMT).ok();
+    match (order, month_first, day_first) {
+        (Some(DateOrder::Mdy), Some(parsed), _) | (Some(DateOrder::Dmy), _, Some(parsed)) => Ok(parsed),
+        (None, Some(a), Some(b)) if a != b => Err(format!("{date} could be month or day first, give --date-format")),
+        (None, Some(parsed), _) | (None, None, Some(parsed)) => Ok(parsed),
+        _ => Err(format!("unknown date format {date}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use super::*;
+
+    #[test]
+    fn it_reads_toggl_reports() {
+        let content = "User,Email,Client,Project,Task,Description,Billable,Start date,Start time,End date,End time,Duration,Tags,Amount ()\n\
+                       Jo,jo@example.com,ACME,Website,,Review,Yes,2026-10-05,08:30:00,2026-10-05,10:00:00,01:30:00,,\n\
+                       Jo,jo@example.com,ACME,Backend,,Fix,Yes,2026-10-05,10:00:00,2026-10-05,12:00:00,02:00:00,,\n";
+        assert_eq!(
+            intervals(content, None).unwrap(),
+            vec![
+                (datetime!(2026-10-05 08:30), datetime!(2026-10-05 10:00)),
+                (datetime!(2026-10-05 10:00), datetime!(2026-10-05 12:00)),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_reads_clockify_reports() {
+        let content = "Project,Client,Description,Task,User,Group,Email,Tags,Billable,Start Date,Start Time,End Date,End Time,Duration (h),Duration (decimal)\n\
+                       Website,ACME,Review,,Jo,,jo@example.com,,Yes,10/05/2026,01:00:00 PM,10/05/2026,05:15:00 PM,04:15:00,4.25\n";
+        assert_eq!(
+            intervals(content, Some(DateOrder::Mdy)).unwrap(),
+            vec![(datetime!(2026-10-05 13:00), datetime!(2026-10-05 17:15))]
+        );
+        assert_eq!(
+            intervals(content, Some(DateOrder::Dmy)).unwrap(),
+            vec![(datetime!(2026-05-10 13:00), datetime!(2026-05-10 17:15))]
+        );
+    }
+
+    #[test]
+    fn it_rejects_ambiguous_dates() {
+        let content = "Start Date,Start Time,End Date,End Time\n\
+                       10/05/2026,13:00,10/05/2026,17:15\n";
+        assert_eq!(
+            intervals(content, None),
+            Err("Invalid start in line 2: 10/05/2026 could be month or day first, give --date-format".into())
+        );
+        let content = "Start Date,Start Time,End Date,End Time\n\
+                       10/25/2026,13:00,10/25/2026,17:15\n";
+        assert_eq!(
+            intervals(content, None).unwrap(),
+            vec![(datetime!(2026-10-25 13:00), datetime!(2026-10-25 17:15))]
+        );
+    }
+
+    #[test]
+    fn it_requires_the_time_columns() {
+        assert_eq!(
+            intervals("Project,Start date\nWebsite,2026-10-05\n", None),
+            Err("Column Start time is missing".into())
+        );
+    }
+}
@@ -1,14 +1,13 @@
-use crate::persistence;
 use crate::spinner_wrap;
 
-use super::{format_hours, local_time_format};
+use super::{format_hours, local_time_format, manual_entry, output};
 use super::{Error, Result};
 use rippling_api::Client;
+use time::{Date, OffsetDateTime, Time};
 
-pub fn status() -> Result<()> {
-    let client: Client = persistence::state().into();
+pub fn status(client: &Client) -> Result<()> {
     let current = spinner_wrap!(client.current_time_entry())?;
-    match current {
+    output::report("status", current.as_ref(), || match &current {
         Some(entry) => {
             let mut msg = format!("Clocked in since {}", local_time_format(entry.start_time));
 
@@ -23,47 +22,81 @@ pub fn status() -> Result<()> {
             msg.push_str(&format!(
                 " (Regular hours: {regular_hours_formatted}, Breaks: {unpaid_break_hours_formatted})"
             ));
-
-            println!("{msg}");
+            msg
         }
-        None => println!("Not clocked in!"),
-    }
+        None => "Not clocked in!".into(),
+    });
     Ok(())
 }
 
-pub fn status_compact() -> Result<()> {
-    let client: Client = persistence::state().into();
+pub fn status_compact(client: &Client) -> Result<()> {
     let current = spinner_wrap!(client.current_time_entry())?;
-    match current {
+    output::report("status", current.as_ref(), || match &current {
         Some(entry) => {
             // If on break, print the break start time
             if let Some(br) = entry.current_break() {
-                println!("Break since {}", local_time_format(br.start_time));
+                format!("Break since {}", local_time_format(br.start_time))
             } else {
                 let regular_hours_formatted = format_hours(entry.regular_hours);
-                println!("Working for {regular_hours_formatted}")
+                format!("Working for {regular_hours_formatted}")
             }
         }
-        None => println!("Clocked out"),
-    }
+        None => "Clocked out".into(),
+    });
     Ok(())
 }
 
-pub fn clock_in() -> Result<()> {
-    let client: Client = persistence::state().into();
+pub fn clock_in(client: &Client) -> Result<()> {
     let entry = spinner_wrap!(client.start_clock())?;
-    println!("Clocked in since {}!", local_time_format(entry.start_time));
+    output::report("clock_in", Some(&entry), || {
+        format!("Clocked in since {}!", local_time_format(entry.start_time))
+    });
     Ok(())
 }
 
-pub fn clock_out() -> Result<()> {
-    let client: Client = persistence::state().into();
+/// Clocks in and moves the start of the new entry back to the given local time
+pub fn clock_in_at(client: &Client, time: Time) -> Result<()> {
+    let date = super::today();
+    let start_time = manual_entry::naive_to_fixed_datetime(date, time);
+    if start_time > OffsetDateTime::now_utc() {
+        return Err(Error::InvalidTime(format!(
+            "{} is in the future",
+            local_time_format(start_time)
+        )));
+    }
+    let entry = spinner_wrap!({
+        // An entry still open from an earlier day is not among the entries of today
+        let mut entries = client.time_entries(super::role(client)?, date, date)?;
+        entries.extend(client.current_time_entry()?);
+        if let Some(other) = entries.iter().find(|e| e.end_time.is_none_or(|end| end > start_time)) {
+            return Err(Error::InvalidTime(format!(
+                "overlaps with entry {}",
+                other.to_offset(super::local_offset_at(other.start_time))
+            )));
+        }
+        let entry = client.start_clock()?;
+        client.update_start_time(&entry.id, start_time).map_err(|e| {
+            eprintln!(
+                "Clocked in since {}, but could not move the start to {}",
+                local_time_format(entry.start_time),
+                local_time_format(start_time)
+            );
+            Error::from(e)
+        })
+    })?;
+    output::report("clock_in", Some(&entry), || {
+        format!("Clocked in since {}!", local_time_format(entry.start_time))
+    });
+    Ok(())
+}
+
+pub fn clock_out(client: &Client) -> Result<()> {
     spinner_wrap!({
         let current = client.current_time_entry()?;
         match current {
             Some(entry) => {
-                client.end_clock(&entry.id)?;
-                println!("Clocked out!");
+                let entry = client.end_clock(&entry.id)?;
+                output::report("clock_out", Some(&entry), || "Clocked out!".into());
                 Ok(())
             }
             None => Err(Error::NotClockedIn),
@@ -71,8 +104,49 @@ pub fn clock_out() -> Result<()> {
     })
 }
 
-pub fn start_break() -> Result<()> {
-    let client: Client = persistence::state().into();
+/// Closes the open entry, or rewrites the end of the last entry on `date`, at the given local time
+pub fn clock_out_at(client: &Client, date: Date, time: Time) -> Result<()> {
+    let end_time = manual_entry::naive_to_fixed_datetime(date, time);
+    if end_time > OffsetDateTime::now_utc() {
+        return Err(Error::InvalidTime(format!(
+            "{} is in the future",
+            local_time_format(end_time)
+        )));
+    }
+    spinner_wrap!({
+        let entry = match client.current_time_entry()? {
+            Some(entry) => entry,
+            None => client
+                .time_entries(super::role(client)?, date, date)?
+                .into_iter()
+                .max_by_key(|e| e.start_time)
+                .ok_or(Error::NotClockedIn)?,
+        };
+        if entry.start_date != date {
+            let started = entry.start_date.format(super::FORMAT_DATE).unwrap();
+            return Err(Error::InvalidTime(format!(
+                "the open entry started on {started}, give --yesterday or close it with `edit`"
+            )));
+        }
+        if end_time <= entry.start_time {
+            return Err(Error::InvalidTime(format!(
+                "entry started at {}",
+                local_time_format(entry.start_time)
+            )));
+        }
+        let break_policy = client.break_policy(&entry.active_policy.break_policy_id)?;
+        let break_type = break_policy.manual_break_type().ok_or(Error::NoManualBreakType)?;
+        // A single update sets the end, so a failure leaves the entry as it was
+        let update = manual_entry::close_entry(&entry, end_time, &break_type.id);
+        let entry = client.update_time_entry(&entry.id, &update)?;
+        output::report("clock_out", Some(&entry), || {
+            format!("Clocked out at {}!", local_time_format(end_time))
+        });
+        Ok(())
+    })
+}
+
+pub fn start_break(client: &Client) -> Result<()> {
     spinner_wrap!({
         let current = client.current_time_entry()?;
 
@@ -86,7 +160,9 @@ pub fn start_break() -> Result<()> {
                     let break_type = break_policy.manual_break_type().ok_or(Error::NoManualBreakType)?;
                     let entry = client.start_break(&entry.id, &break_type.id)?;
                     let brk = entry.current_break().unwrap().to_owned();
-                    println!("Started break at {}!", local_time_format(brk.start_time));
+                    output::report("start_break", Some(&entry), || {
+                        format!("Started break at {}!", local_time_format(brk.start_time))
+                    });
                     Ok(())
                 }
             }
@@ -94,8 +170,7 @@ pub fn start_break() -> Result<()> {
     })
 }
 
-pub fn end_break() -> Result<()> {
-    let client: Client = persistence::state().into();
+pub fn end_break(client: &Client) -> Result<()> {
     spinner_wrap!({
         let current = client.current_time_entry()?;
 
@@ -105,15 +180,52 @@ pub fn end_break() -> Result<()> {
                 None => Err(Error::NotOnBreak),
                 Some(br) => {
                     let res = client.end_break(&entry.id, &br.break_type_id)?;
-                    let brk = res.breaks.into_iter().last().ok_or(Error::UnexpectedResponse)?;
-                    println!(
-                        "Stopped break at {}, after {} hours!",
-                        local_time_format(brk.end_time.unwrap()),
-                        format_hours(brk.duration().unwrap().whole_minutes() as f32 / 60.0)
-                    );
+                    let brk = res.breaks.last().ok_or(Error::UnexpectedResponse)?;
+                    output::report("end_break", Some(&res), || {
+                        format!(
+                            "Stopped break at {}, after {} hours!",
+                            local_time_format(brk.end_time.unwrap()),
+                            format_hours(brk.duration().unwrap().whole_minutes() as f32 / 60.0)
+                        )
+                    });
                     Ok(())
                 }
             },
         }
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use utilities::mocking;
+
+    use super::*;
+
+    #[test]
+    fn clock_in_at_refuses_to_overlap_an_open_entry() {
+        let mut server = mocking::FakeRippling::new();
+        let client = rippling_api::ClientBuilder::new()
+            .root(url::Url::parse(&server.url()).unwrap())
+            .build("access-token".to_owned())
+            .with_company_and_role("some-company-id".to_owned(), "some-role-id".to_owned());
+        // Nothing started today, but an entry from an earlier day is still open
+        let _today = server
+            .mock("GET", mocking::Matcher::Regex("startDate__gte".into()))
+            .with_header("content-type", "application/json")
+            .with_body("[]")
+            .create();
+        let _current = server
+            .mock("GET", mocking::Matcher::Regex("endTime=".into()))
+            .with_header("content-type", "application/json")
+            .with_body(format!("[{}]", mocking::read_fixture("time_entry")))
+            .create();
+        let start_clock = server
+            .mock("POST", "/time_tracking/api/time_entries/start_clock")
+            .expect(0)
+            .create();
+
+        let result = clock_in_at(&client, Time::MIDNIGHT);
+        assert!(matches!(result, Err(Error::InvalidTime(reason)) if reason.starts_with("overlaps")));
+        start_clock.assert();
+    }
+}
@@ -0,0 +1,157 @@
+use std::fmt::Write;
+
+use clap::Parser;
+use rippling_api::{time_entries::TimeEntry, Client};
+use time::{macros::format_description, Date, Duration};
+
+use crate::{persistence, spinner_wrap};
+
+use super::{format_hours, local_time_format, output, Result};
+
+const DATE_FMT: &[time::format_description::FormatItem] =
+    format_description!("[weekday repr:short] [day] [month repr:short]");
+
+/// List past entries with daily and weekly totals
+#[derive(Debug, Parser)]
+pub struct Command {
+    /// Entries of the current week (default)
+    #[arg(short, long, conflicts_with_all = ["month", "from"])]
+    pub week: bool,
+    /// Entries of the current month
+    #[arg(short, long, conflicts_with_all = ["week", "from"])]
+    pub month: bool,
+    /// First day to list, for example 2023-01-16
+    #[arg(long, value_parser = super::parse_date)]
+    pub from: Option<Date>,
+    /// Last day to list, defaults to today
+    #[arg(long, value_parser = super::parse_date, requires = "from")]
+    pub to: Option<Date>,
+}
+
+/// Entrypoint for this module
+pub fn execute(cmd: &Command) -> Result<()> {
+    let (from, to) = date_range(cmd, super::today());
+    let client: Client = persistence::state().into();
+    let mut entries = spinner_wrap!(client.time_entries(super::role(&client)?, from, to))?;
+    entries.sort_by_key(|e| e.start_time);
+    let report = output::EntriesReport { event: "log", entries: entries.iter().map(output::Entry::from).collect() };
+    output::print(&report, || render_table(&entries));
+    Ok(())
+}
+
+fn date_range(cmd: &Command, today: Date) -> (Date, Date) {
+    if let Some(from) = cmd.from {
+        (from, cmd.to.unwrap_or(today))
+    } else if cmd.month {
+        let last = today.month().length(today.year());
+        (today.replace_day(1).unwrap(), today.replace_day(last).unwrap())
+    } else {
+        let monday = today - Duration::days(i64::from(today.weekday().number_days_from_monday()));
+        (monday, monday + Duration::days(6))
+    }
+}
+
+fn render_table(entries: &[TimeEntry]) -> String {
+    let mut out = String::new();
+    writeln!(
+        out,
+        "{:<12} {:>5} {:>5} {:>6} {:>6}",
+        "Date", "Start", "End", "Breaks", "Hours"
+    )
+    .unwrap();
+
+    let mut day_total = 0.0;
+    let mut week_total = 0.0;
+    let mut total = 0.0;
+    let mut iter = entries.iter().peekable();
+    while let Some(entry) = iter.next() {
+        writeln!(
+            out,
+            "{:<12} {:>5} {:>5} {:>6} {:>6}",
+            entry.start_date.format(DATE_FMT).unwrap(),
+            local_time_format(entry.start_time),
+            entry.end_time.map(local_time_format).unwrap_or("-".into()),
+            format_hours(entry.unpaid_break_hours),
+            format_hours(entry.regular_hours)
+        )
+        .unwrap();
+        day_total += entry.regular_hours;
+        week_total += entry.regular_hours;
+        total += entry.regular_hours;
+
+        let next = iter.peek();
+        if next.is_none_or(|n| n.start_date != entry.start_date) {
+            writeln!(out, "{:<32} {:>6}", "  Day total", format_hours(day_total)).unwrap();
+            day_total = 0.0;
+        }
+        if next.is_none_or(|n| n.start_date.iso_week() != entry.start_date.iso_week()) {
+            let label = format!("Week {} total", entry.start_date.iso_week());
+            writeln!(out, "{label:<32} {:>6}", format_hours(week_total)).unwrap();
+            week_total = 0.0;
+        }
+    }
+    writeln!(out, "{:<32} {:>6}", "Total", format_hours(total)).unwrap();
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::date;
+    use utilities::mocking;
+
+    use super::*;
+
+    fn entry_on(date: Date, regular_hours: &str) -> TimeEntry {
+        let mut json: serde_json::Value = serde_json::from_str(&mocking::read_fixture("entry_closed")).unwrap();
+        json["startDate"] = date.to_string().into();
+        json["regularHours"] = regular_hours.into();
+        serde_json::from_value(json).unwrap()
+    }
+
+    fn command() -> Command {
+        Command { week: false, month: false, from: None, to: None }
+    }
+
+    #[test]
+    fn date_range() {
+        let today = date!(2023 - 01 - 19);
+        assert_eq!(
+            super::date_range(&command(), today),
+            (date!(2023 - 01 - 16), date!(2023 - 01 - 22))
+        );
+        assert_eq!(
+            super::date_range(&Command { month: true, ..command() }, today),
+            (date!(2023 - 01 - 01), date!(2023 - 01 - 31))
+        );
+        assert_eq!(
+            super::date_range(&Command { from: Some(date!(2023 - 01 - 02)), ..command() }, today),
+            (date!(2023 - 01 - 02), today)
+        );
+    }
+
+    #[test]
+    fn render_table_adds_day_and_week_totals() {
+        let entries = [
+            entry_on(date!(2023 - 01 - 19), "4"),
+            entry_on(date!(2023 - 01 - 19), "3.5"),
+            entry_on(date!(2023 - 01 - 20), "8"),
+            entry_on(date!(2023 - 01 - 23), "6.25"),
+        ];
+        let totals: Vec<String> = render_table(&entries)
+            .lines()
+            .filter(|line| line.contains("total") || line.starts_with("Total"))
+            .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+            .collect();
+        assert_eq!(
+            totals,
+            vec![
+                "Day total 7:30",
+                "Day total 8:00",
+                "Week 3 total 15:30",
+                "Day total 6:15",
+                "Week 4 total 6:15",
+                "Total 21:45"
+            ]
+        );
+    }
+}
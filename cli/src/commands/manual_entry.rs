@@ -1,15 +1,29 @@
-use clap::{arg, Parser};
-use inquire::Confirm;
+use clap::Parser;
 use regex::Regex;
-use std::{result::Result as StdResult, thread};
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    result::Result as StdResult,
+    thread,
+};
 use time::{Date, Duration, OffsetDateTime, PrimitiveDateTime, Time};
 
 use crate::{persistence, spinner_wrap};
 
-use super::pto::{self, CheckOutcome};
-use super::Result;
+use super::output;
+use super::pto::{self, CheckOutcome, DaysOff};
+use super::{DateRange, Result};
 
-use rippling_api::{self, break_policy::BreakPolicy, time_entries::NewTimeEntry, Client};
+use rippling_api::{
+    self,
+    break_policy::BreakPolicy,
+    time_entries::{NewTimeEntry, TimeEntry},
+    Client,
+};
+
+mod ics;
 
 #[derive(Clone, Debug)]
 pub struct TimeRange {
@@ -29,37 +43,200 @@ pub struct Command {
     /// Bypass prompt with a yes answer
     #[arg(short, long)]
     pub yes: bool,
-    #[arg(value_parser = parse_input_shifts)]
+    #[arg(value_parser = parse_input_shifts, required_unless_present = "from_ics")]
     pub ranges: Vec<TimeRange>,
+    /// Add one entry per day from the events of an iCalendar file instead
+    #[arg(long, conflicts_with_all = ["ranges", "days_ago"], requires = "range")]
+    pub from_ics: Option<PathBuf>,
+    /// Days to take from the iCalendar file, for example 2026-10-01..2026-10-31
+    #[arg(long, value_parser = super::parse_date_range, requires = "from_ics")]
+    pub range: Option<DateRange>,
 }
 
 /// Entrypoint for this module
 pub fn execute(cmd: &Command) -> Result<()> {
+    let client: Client = persistence::state().into();
+    if let (Some(path), Some(range)) = (&cmd.from_ics, cmd.range) {
+        return execute_ics(&client, cmd, path, range);
+    }
     let date = super::today()
         .checked_sub(Duration::days(i64::from(cmd.days_ago.unwrap_or(0))))
         .unwrap();
-    let entry = draft_entry(date, &cmd.ranges, cmd.check)?;
-    if cmd.yes || Confirm::new(&format!("Create entry {entry}?")).prompt().unwrap() {
-        submit_entry(entry)?;
+    let entry = draft_entry(&client, date, &cmd.ranges, cmd.check)?;
+    if cmd.yes || super::confirm(&format!("Create entry {entry}?"))? {
+        submit_entry(&client, entry)?;
     }
     Ok(())
 }
 
-fn draft_entry(date: Date, ranges: &Vec<TimeRange>, check: bool) -> Result<NewTimeEntry> {
-    let policy_thread = thread::spawn(|| -> StdResult<BreakPolicy, rippling_api::Error> {
-        let client: Client = persistence::state().into();
-        let policy = client.active_break_policy()?;
-        client.break_policy(&policy.break_policy)
+fn execute_ics(client: &Client, cmd: &Command, path: &Path, range: DateRange) -> Result<()> {
+    let events = ics::events(&fs::read_to_string(path)?).map_err(super::Error::InvalidFile)?;
+    let days = ranges_by_day(events, range, Duration::ZERO);
+    submit_days(client, days, cmd.check, cmd.yes)
+}
+
+/// Work ranges per day within `range`, from intervals in local time
+///
+/// Overlapping intervals and those less than `min_gap` apart are merged, only longer gaps become breaks.
+/// Intervals spanning midnight are ignored.
+pub(super) fn ranges_by_day(
+    intervals: impl IntoIterator<Item = (PrimitiveDateTime, PrimitiveDateTime)>,
+    range: DateRange,
+    min_gap: Duration,
+) -> BTreeMap<Date, Vec<TimeRange>> {
+    let mut days: BTreeMap<Date, Vec<TimeRange>> = BTreeMap::new();
+    for (start, end) in intervals {
+        if start.date() != end.date() || start >= end || start.date() < range.from || start.date() > range.to {
+            continue;
+        }
+        days.entry(start.date())
+            .or_default()
+            .push(TimeRange { start_time: start.time(), end_time: end.time() });
+    }
+    for ranges in days.values_mut() {
+        ranges.sort_by_key(|r| r.start_time);
+        let mut merged: Vec<TimeRange> = Vec::new();
+        for range in ranges.drain(..) {
+            match merged.last_mut() {
+                // Touching or overlapping ranges are always merged
+                Some(last) if range.start_time - last.end_time < min_gap.max(Duration::SECOND) => {
+                    last.end_time = last.end_time.max(range.end_time)
+                }
+                _ => merged.push(range),
+            }
+        }
+        *ranges = merged;
+    }
+    days
+}
+
+/// Shows the entry of every day, then submits all of them after a single confirmation
+///
+/// Every day is submitted on its own, a failed day is reported and fails the command
+/// only after the remaining days were submitted.
+pub(super) fn submit_days(client: &Client, days: BTreeMap<Date, Vec<TimeRange>>, check: bool, yes: bool) -> Result<()> {
+    let (break_policy, days_off) = spinner_wrap!((
+        fetch_break_policy(client)?,
+        if check { Some(DaysOff::fetch(client)?) } else { None }
+    ));
+    let btype = break_policy
+        .manual_break_type()
+        .ok_or(super::Error::NoManualBreakType)?;
+
+    let mut entries: Vec<(Date, NewTimeEntry)> = Vec::new();
+    for (date, ranges) in days {
+        if let Some(days_off) = &days_off {
+            let pto = days_off.check(date);
+            if !matches!(pto, CheckOutcome::WorkingDay) {
+                output::note(&format!("Skipping {}: {pto}", date.format(super::FORMAT_DATE).unwrap()));
+                continue;
+            }
+        }
+        entries.push((date, build_entry(date, &ranges, &btype.id)));
+    }
+    if entries.is_empty() {
+        output::note("Nothing found in the given range");
+        return Ok(());
+    }
+
+    for (_, entry) in &entries {
+        output::note(&entry.to_string());
+    }
+    let question = format!("Create these {} entries?", entries.len());
+    if !yes && !super::confirm(&question)? {
+        return Ok(());
+    }
+    let results: Vec<(Date, StdResult<TimeEntry, String>)> = entries
+        .iter()
+        .map(|(date, entry)| {
+            (
+                *date,
+                spinner_wrap!(client.create_time_entry(entry)).map_err(|e| e.to_string()),
+            )
+        })
+        .collect();
+    let report = DaysReport {
+        event: "manual",
+        days: results.iter().map(|(date, r)| SubmittedDay::new(*date, r)).collect(),
+    };
+    output::print(&report, || {
+        results
+            .iter()
+            .map(|(date, result)| {
+                let date = date.format(super::FORMAT_DATE).unwrap();
+                match result {
+                    Ok(created) => format!("{date}: {}", added_message(created)),
+                    Err(e) => format!("{date}: failed, {e}"),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
     });
+    let failed: Vec<Date> = results
+        .iter()
+        .filter(|(_, r)| r.is_err())
+        .map(|(date, _)| *date)
+        .collect();
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(super::Error::DaysFailed(failed))
+    }
+}
+
+/// Result of submitting several days, as printed with `--output json|yaml`
+#[derive(Debug, Serialize)]
+struct DaysReport {
+    event: &'static str,
+    days: Vec<SubmittedDay>,
+}
+
+#[derive(Debug, Serialize)]
+struct SubmittedDay {
+    date: Date,
+    /// The created entry, `null` when the day failed
+    entry: Option<output::Entry>,
+    /// Why the day failed
+    error: Option<String>,
+}
+
+impl SubmittedDay {
+    fn new(date: Date, result: &StdResult<TimeEntry, String>) -> Self {
+        match result {
+            Ok(entry) => Self { date, entry: Some(output::Entry::from(entry)), error: None },
+            Err(e) => Self { date, entry: None, error: Some(e.clone()) },
+        }
+    }
+}
+
+pub(super) fn draft_entry(client: &Client, date: Date, ranges: &[TimeRange], check: bool) -> Result<NewTimeEntry> {
+    let policy_thread = {
+        let client = client.clone();
+        thread::spawn(move || fetch_break_policy(&client))
+    };
 
     if check {
-        let pto = pto::check(date).unwrap();
+        let pto = pto::check(client, date)?;
         if let CheckOutcome::WorkingDay = pto {
         } else {
             return Err(super::Error::NoWorkingDay(pto));
         }
     }
 
+    let break_policy = policy_thread.join().unwrap()?;
+    let btype = break_policy
+        .manual_break_type()
+        .ok_or(super::Error::NoManualBreakType)?;
+    Ok(build_entry(date, ranges, &btype.id))
+}
+
+pub(super) fn fetch_break_policy(client: &Client) -> StdResult<BreakPolicy, rippling_api::Error> {
+    let policy = client.active_break_policy()?;
+    client.break_policy(&policy.break_policy)
+}
+
+/// Turns the worked `ranges` of a day into an entry, adding the statutory minimum breaks
+pub(super) fn build_entry(date: Date, ranges: &[TimeRange], break_type_id: &str) -> NewTimeEntry {
     // List of times where either work started or stopped
     let mut events: Vec<Time> = Vec::new();
     for range in ranges {
@@ -78,33 +255,34 @@ fn draft_entry(date: Date, ranges: &Vec<TimeRange>, check: bool) -> Result<NewTi
     let end_time = events.pop().unwrap();
     entry.add_shift(start_time, end_time);
 
-    let break_policy = policy_thread.join().unwrap().unwrap();
-    let btype = break_policy.manual_break_type().unwrap();
     for pair in events.chunks(2) {
-        entry.add_break(btype.id.clone(), pair[0], pair[1]);
+        entry.add_break(break_type_id.to_owned(), pair[0], pair[1]);
     }
-    Ok(entry)
+    entry
 }
 
-fn submit_entry(entry: NewTimeEntry) -> super::Result<()> {
-    let client: Client = persistence::state().into();
+fn submit_entry(client: &Client, entry: NewTimeEntry) -> super::Result<()> {
     let entry = spinner_wrap!(client.create_time_entry(&entry))?;
-    println!(
+    output::report("manual", Some(&entry), || added_message(&entry));
+    Ok(())
+}
+
+fn added_message(entry: &TimeEntry) -> String {
+    format!(
         "Added entry from {} to {}",
         super::local_time_format(entry.start_time),
         super::local_time_format(entry.end_time.unwrap())
-    );
-    Ok(())
+    )
 }
 
-fn naive_to_fixed_datetime(date: Date, time: Time) -> OffsetDateTime {
+pub(super) fn naive_to_fixed_datetime(date: Date, time: Time) -> OffsetDateTime {
     let datetime: PrimitiveDateTime = PrimitiveDateTime::new(date, time);
     datetime.assume_offset(super::local_offset_estimated_at(datetime))
 }
 
 /// Sets the regulatory required minimum break per shift according to German labor law
-fn setup_minimum_breaks(input: &Vec<Time>) -> Vec<Time> {
-    assert!(input.len() % 2 == 0);
+fn setup_minimum_breaks(input: &[Time]) -> Vec<Time> {
+    assert!(input.len().is_multiple_of(2));
     let mut out: Vec<Time> = Vec::new();
     for pair in input.chunks_exact(2) {
         let duration = pair[1] - pair[0];
@@ -120,6 +298,72 @@ fn setup_minimum_breaks(input: &Vec<Time>) -> Vec<Time> {
     out
 }
 
+/// Closes `entry` at `end_time`, adding break time where the statutory minimum is not met
+///
+/// Existing shifts are kept, only the last one ends at `end_time`.
+pub(super) fn close_entry(entry: &TimeEntry, end_time: OffsetDateTime, break_type_id: &str) -> NewTimeEntry {
+    let mut shifts: Vec<(OffsetDateTime, OffsetDateTime)> = entry
+        .shifts
+        .iter()
+        .filter(|s| s.start_time < end_time)
+        .map(|s| (s.start_time, s.end_time.unwrap_or(end_time).min(end_time)))
+        .collect();
+    shifts.sort_by_key(|(start, _)| *start);
+    match shifts.last_mut() {
+        Some(last) => last.1 = end_time,
+        None => shifts.push((entry.start_time, end_time)),
+    }
+
+    // Existing breaks, cut off at the new end time
+    let mut breaks: Vec<(String, OffsetDateTime, OffsetDateTime)> = entry
+        .breaks
+        .iter()
+        .filter(|b| b.start_time < end_time)
+        .map(|b| {
+            let break_end = b.end_time.unwrap_or(end_time).min(end_time);
+            (b.break_type_id.clone(), b.start_time, break_end)
+        })
+        .collect();
+    breaks.sort_by_key(|(_, start, _)| *start);
+
+    // Time between shifts counts like a break
+    let mut pauses: Vec<(OffsetDateTime, OffsetDateTime)> =
+        breaks.iter().map(|(_, start, end)| (*start, *end)).collect();
+    pauses.extend(shifts.windows(2).map(|pair| (pair[0].1, pair[1].0)));
+    pauses.sort_by_key(|(start, _)| *start);
+
+    let first_start = shifts[0].0;
+    let taken: Duration = pauses.iter().map(|(start, end)| *end - *start).sum();
+    let missing = minimum_break_for(end_time - first_start) - taken;
+    if missing.is_positive() {
+        // Put the missing break into the middle of the longest stretch of work
+        let mut bounds = vec![first_start];
+        for (start, end) in &pauses {
+            bounds.push(*start);
+            bounds.push(*end);
+        }
+        bounds.push(end_time);
+        let (from, to) = bounds
+            .chunks_exact(2)
+            .map(|pair| (pair[0], pair[1]))
+            .max_by_key(|(from, to)| *to - *from)
+            .unwrap();
+        let break_start = from + (to - from) / 2 - missing / 2;
+        breaks.push((break_type_id.to_owned(), break_start, break_start + missing));
+        breaks.sort_by_key(|(_, start, _)| *start);
+    }
+
+    let mut new_entry = NewTimeEntry::new();
+    for (start, end) in shifts {
+        new_entry.add_shift(start, end);
+    }
+    for (break_type, start, end) in breaks {
+        new_entry.add_break(break_type, start, end);
+    }
+    new_entry.notes = entry.notes.clone();
+    new_entry
+}
+
 /// Calculate minimum break duration according to German labor law
 fn minimum_break_for(duration: Duration) -> Duration {
     let mut dur = Duration::minutes(0);
@@ -136,6 +380,20 @@ fn minimum_break_for(duration: Duration) -> Duration {
     dur
 }
 
+/// Makes sure every range ends after it starts and ranges follow each other without overlap
+pub(super) fn check_ranges(ranges: &[TimeRange]) -> StdResult<(), String> {
+    if ranges.is_empty() {
+        return Err("No shifts given".into());
+    }
+    if ranges.iter().any(|r| r.end_time <= r.start_time) {
+        return Err("Shifts must end after they start".into());
+    }
+    if ranges.windows(2).any(|w| w[1].start_time < w[0].end_time) {
+        return Err("Shifts must be in order and must not overlap".into());
+    }
+    Ok(())
+}
+
 pub fn parse_input_shifts(s: &str) -> StdResult<TimeRange, String> {
     let re = Regex::new(r"^(?P<h1>\d{1,2})(?::(?P<m1>\d{2}))?-(?P<h2>\d{1,2})(?::(?P<m2>\d{2}))?$").unwrap();
     if let Some(m) = re.captures(s) {
@@ -152,7 +410,78 @@ pub fn parse_input_shifts(s: &str) -> StdResult<TimeRange, String> {
 
 #[cfg(test)]
 mod tests {
-    use time::Duration;
+    use std::collections::BTreeMap;
+
+    use rippling_api::time_entries::TimeEntry;
+    use time::{
+        macros::{date, datetime, time},
+        Duration,
+    };
+    use utilities::mocking;
+
+    fn open_entry() -> TimeEntry {
+        // Started 2023-01-19 08:22:25 UTC, no breaks
+        serde_json::from_str(&mocking::read_fixture("time_entry")).unwrap()
+    }
+
+    #[test]
+    fn close_entry_adds_statutory_break() {
+        let entry = super::close_entry(&open_entry(), datetime!(2023-01-19 16:22:25 UTC), "break-type");
+        assert_eq!(
+            entry.shifts.first().unwrap().end_time,
+            datetime!(2023-01-19 16:22:25 UTC)
+        );
+        let brk = entry.breaks.first().unwrap();
+        assert_eq!(brk.start_time, datetime!(2023-01-19 12:07:25 UTC));
+        assert_eq!(brk.end_time, datetime!(2023-01-19 12:37:25 UTC));
+    }
+
+    #[test]
+    fn close_entry_keeps_sufficient_breaks() {
+        let mut entry = open_entry();
+        let mut closed: TimeEntry = serde_json::from_str(&mocking::read_fixture("entry_closed")).unwrap();
+        entry.breaks.append(&mut closed.breaks);
+        let entry = super::close_entry(&entry, datetime!(2023-01-19 15:00 UTC), "break-type");
+        assert_eq!(entry.breaks.len(), 1);
+        assert_eq!(entry.breaks.first().unwrap().break_type_id, "break-id-1");
+    }
+
+    #[test]
+    fn close_entry_keeps_shift_boundaries() {
+        let mut json: serde_json::Value = serde_json::from_str(&mocking::read_fixture("time_entry")).unwrap();
+        json["breaks"] = serde_json::json!([]);
+        json["jobShifts"] = serde_json::json!([
+            {"startTime": "2023-01-19T08:00:00Z", "endTime": "2023-01-19T11:00:00Z"},
+            {"startTime": "2023-01-19T12:00:00Z", "endTime": null}
+        ]);
+        let entry: TimeEntry = serde_json::from_value(json).unwrap();
+        let entry = super::close_entry(&entry, datetime!(2023-01-19 16:00 UTC), "break-type");
+        let shifts: Vec<_> = entry.shifts.iter().map(|s| (s.start_time, s.end_time)).collect();
+        assert_eq!(
+            shifts,
+            vec![
+                (datetime!(2023-01-19 08:00 UTC), datetime!(2023-01-19 11:00 UTC)),
+                (datetime!(2023-01-19 12:00 UTC), datetime!(2023-01-19 16:00 UTC)),
+            ]
+        );
+        // The hour between the shifts already covers the statutory break
+        assert!(entry.breaks.is_empty());
+    }
+
+    #[test]
+    fn ranges_by_day_turns_long_gaps_into_breaks() {
+        let intervals = [
+            (datetime!(2026-10-05 08:30), datetime!(2026-10-05 10:00)),
+            (datetime!(2026-10-05 10:05), datetime!(2026-10-05 12:00)),
+            (datetime!(2026-10-05 12:30), datetime!(2026-10-05 17:00)),
+        ];
+        let range = super::DateRange { from: date!(2026 - 10 - 05), to: date!(2026 - 10 - 05) };
+        let days = super::ranges_by_day(intervals, range, Duration::minutes(15));
+        let ranges = days.get(&date!(2026 - 10 - 05)).unwrap();
+        assert_eq!(ranges.len(), 2);
+        assert_eq!((ranges[0].start_time, ranges[0].end_time), (time!(08:30), time!(12:00)));
+        assert_eq!((ranges[1].start_time, ranges[1].end_time), (time!(12:30), time!(17:00)));
+    }
 
     #[test]
     fn minimum_break_for() {
@@ -170,4 +499,40 @@ mod tests {
             assert_eq!(super::minimum_break_for(Duration::minutes(w)), Duration::minutes(b));
         }
     }
+
+    #[test]
+    fn submit_days_continues_after_a_failed_day() {
+        let mut server = mocking::FakeRippling::new();
+        let client = rippling_api::ClientBuilder::new()
+            .root(url::Url::parse(&server.url()).unwrap())
+            .build("access-token".to_owned())
+            .with_company_and_role("some-company-id".to_owned(), "some-role-id".to_owned());
+        server.mock_active_policy();
+        server.mock_break_policy("some-break-policy-id");
+        let mock_day = |server: &mut mocking::FakeRippling, date: &str, status: usize| {
+            server
+                .mock("POST", "/time_tracking/api/time_entries")
+                .match_body(mocking::Matcher::Regex(date.into()))
+                .with_status(status)
+                .with_header("content-type", "application/json")
+                .with_body(mocking::read_fixture("entry_closed"))
+                .expect(1)
+                .create()
+        };
+        let first = mock_day(&mut server, "2023-01-19", 200);
+        let second = mock_day(&mut server, "2023-01-20", 400);
+        let third = mock_day(&mut server, "2023-01-23", 200);
+
+        let day = || vec![super::TimeRange { start_time: time!(9:00), end_time: time!(17:00) }];
+        let days = BTreeMap::from([
+            (date!(2023 - 01 - 19), day()),
+            (date!(2023 - 01 - 20), day()),
+            (date!(2023 - 01 - 23), day()),
+        ]);
+        let result = super::submit_days(&client, days, false, true);
+        assert!(matches!(result, Err(crate::commands::Error::DaysFailed(failed)) if failed == [date!(2023 - 01 - 20)]));
+        first.assert();
+        second.assert();
+        third.assert();
+    }
 }
@@ -0,0 +1,157 @@
+use time::{macros::format_description, PrimitiveDateTime, UtcOffset};
+
+const DATETIME_FMT: &[time::format_description::FormatItem] =
+    format_description!("[year][month][day]T[hour][minute][second]");
+
+/// Start and end of each timed VEVENT in local time
+pub fn events(content: &str) -> Result<Vec<(PrimitiveDateTime, PrimitiveDateTime)>, String> {
+    let mut events = Vec::new();
+    let mut in_event = false;
+    let (mut start, mut end) = (None, None);
+    for line in unfold(content) {
+        let (name, value) = match line.split_once(':') {
+            Some(pair) => pair,
+            None => continue,
+        };
+        let (name, params) = name.split_once(';').unwrap_or((name, ""));
+        match (name, value) {
+            ("BEGIN", "VEVENT") => {
+                in_event = true;
+                (start, end) = (None, None);
+            }
+            ("END", "VEVENT") => {
+                in_event = false;
+                if let (Some(start), Some(end)) = (start, end) {
+                    events.push((start, end));
+                }
+            }
+            ("DTSTART", _) if in_event => start = parse_datetime(params, value)?,
+            ("DTEND", _) if in_event => end = parse_datetime(params, value)?,
+            _ => {}
+        }
+    }
+    Ok(events)
+}
+
+/// Joins lines which were folded by a leading space or tab
+fn unfold(content: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in content.lines() {
+        match line.strip_prefix([' ', '\t']) {
+            Some(rest) if !lines.is_empty() => lines.last_mut().unwrap().push_str(rest),
+            _ => lines.push(line.to_owned()),
+        }
+    }
+    lines
+}
+
+/// Times in UTC or with a known TZID are converted to the local offset, floating times are taken as local
+///
+/// A TZID which is not in the time zone database is rejected, its times could be off by hours.
+fn parse_datetime(params: &str, value: &str) -> Result<Option<PrimitiveDateTime>, String> {
+    if params.split(';').any(|p| p == "VALUE=DATE") {
+        return Ok(None);
+    }
+    let tzid = params
+        .split(';')
+        .find_map(|p| p.strip_prefix("TZID="))
+        .map(|tzid| tzid.trim_matches('"'));
+    let Some(datetime) = PrimitiveDateTime::parse(value.trim_end_matches('Z'), DATETIME_FMT).ok() else {
+        return Ok(None);
+    };
+    let offset = if value.ends_with('Z') {
+        UtcOffset::UTC
+    } else if let Some(tzid) = tzid {
+        offset_in(tzid, datetime).ok_or_else(|| format!("unknown time zone {tzid}"))?
+    } else {
+        return Ok(Some(datetime));
+    };
+    Ok(Some(super::super::local_datetime(datetime.assume_offset(offset))))
+}
+
+/// Offset of the time zone `tzid` at the wall clock time `datetime`
+fn offset_in(tzid: &str, datetime: PrimitiveDateTime) -> Option<UtcOffset> {
+    let tz = tzdb::tz_by_name(tzid)?;
+    let offset_at = |timestamp: i64| {
+        let seconds = tz.find_local_time_type(timestamp).ok()?.ut_offset();
+        UtcOffset::from_whole_seconds(seconds).ok()
+    };
+    // The offset at the wall clock time read as UTC is off by at most one transition, so look once more
+    let guess = offset_at(datetime.assume_utc().unix_timestamp())?;
+    offset_at(datetime.assume_offset(guess).unix_timestamp())
+}
+
+#[cfg(test)]
+mod tests {
+    use time::{
+        macros::{date, time},
+        Duration,
+    };
+
+    use super::super::{ranges_by_day, DateRange};
+    use super::*;
+
+    const CALENDAR: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+BEGIN:VEVENT\r
+SUMMARY:Work\r
+DTSTART;TZID=Europe/Berlin:20261005T083000\r
+DTEND;TZID=Europe/Berlin:20261005T120000\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+SUMMARY:Work\r
+DTSTART:20261005T100000Z\r
+DTEND:20261005T1\r
+ 53000Z\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+SUMMARY:Conference\r
+DTSTART;VALUE=DATE:20261006\r
+DTEND;VALUE=DATE:20261007\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+SUMMARY:Work\r
+DTSTART:20261101T090000\r
+DTEND:20261101T170000\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    #[test]
+    fn it_reads_ranges_per_day() {
+        let range = DateRange { from: date!(2026 - 10 - 01), to: date!(2026 - 10 - 31) };
+        let days = ranges_by_day(events(CALENDAR).unwrap(), range, Duration::ZERO);
+        assert_eq!(days.len(), 1);
+        let ranges = days.get(&date!(2026 - 10 - 05)).unwrap();
+        // 10:00Z is 12:00 in CEST, which touches the first event
+        assert_eq!(ranges.len(), 1);
+        assert_eq!(ranges[0].start_time, time!(08:30));
+        assert_eq!(ranges[0].end_time, time!(17:30));
+    }
+
+    #[test]
+    fn it_converts_times_of_other_time_zones() {
+        let calendar = "BEGIN:VEVENT\r
+DTSTART;TZID=America/New_York:20261005T030000\r
+DTEND;TZID=\"America/New_York\":20261005T110000\r
+END:VEVENT\r
+";
+        let events = events(calendar).unwrap();
+        // New York is 6 hours behind Berlin in October
+        assert_eq!(events[0].0, date!(2026 - 10 - 05).with_time(time!(09:00)));
+        assert_eq!(events[0].1, date!(2026 - 10 - 05).with_time(time!(17:00)));
+    }
+
+    #[test]
+    fn it_rejects_unknown_time_zones() {
+        let calendar = "BEGIN:VEVENT\r
+DTSTART;TZID=W. Europe Standard Time:20261005T090000\r
+DTEND;TZID=W. Europe Standard Time:20261005T170000\r
+END:VEVENT\r
+";
+        assert_eq!(
+            events(calendar),
+            Err("unknown time zone W. Europe Standard Time".into())
+        );
+    }
+}
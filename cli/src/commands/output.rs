@@ -0,0 +1,167 @@
+use std::sync::OnceLock;
+
+use clap::ValueEnum;
+use rippling_api::time_entries::{TimeEntry, TimeEntryBreak};
+use serde::Serialize;
+use time::{serde::rfc3339, OffsetDateTime};
+
+static FORMAT: OnceLock<Format> = OnceLock::new();
+
+/// How results of commands are printed
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// Human readable sentences
+    #[default]
+    Text,
+    Json,
+    Yaml,
+}
+
+pub fn init(format: Format) {
+    FORMAT.set(format).unwrap();
+}
+
+pub fn format() -> Format {
+    *FORMAT.get_or_init(Format::default)
+}
+
+/// Result of a command, as printed with `--output json|yaml`
+#[derive(Debug, Serialize)]
+pub struct Report {
+    /// Name of the command, for example `clock_in`
+    pub event: &'static str,
+    /// The affected entry, `null` when there is none (for example `status` while clocked out)
+    pub entry: Option<Entry>,
+}
+
+/// Result of a command listing several entries, as printed with `--output json|yaml`
+#[derive(Debug, Serialize)]
+pub struct EntriesReport {
+    pub event: &'static str,
+    pub entries: Vec<Entry>,
+}
+
+/// Failure of a command, as printed with `--output json|yaml`
+#[derive(Debug, Serialize)]
+pub struct ErrorReport {
+    pub event: &'static str,
+    pub error: String,
+}
+
+/// Stable representation of a `TimeEntry`, with all times in the local offset
+#[derive(Debug, Serialize)]
+pub struct Entry {
+    pub id: String,
+    #[serde(with = "rfc3339")]
+    pub start: OffsetDateTime,
+    #[serde(with = "rfc3339::option")]
+    pub end: Option<OffsetDateTime>,
+    pub breaks: Vec<Break>,
+    pub on_break: bool,
+    pub regular_hours: f32,
+    pub unpaid_break_hours: f32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Break {
+    #[serde(with = "rfc3339")]
+    pub start: OffsetDateTime,
+    #[serde(with = "rfc3339::option")]
+    pub end: Option<OffsetDateTime>,
+}
+
+impl From<&TimeEntry> for Entry {
+    fn from(entry: &TimeEntry) -> Self {
+        let entry = entry.to_offset(super::local_offset_at(entry.start_time));
+        Self {
+            on_break: entry.current_break().is_some(),
+            breaks: entry.breaks.iter().map(Break::from).collect(),
+            id: entry.id,
+            start: entry.start_time,
+            end: entry.end_time,
+            regular_hours: entry.regular_hours,
+            unpaid_break_hours: entry.unpaid_break_hours,
+        }
+    }
+}
+
+impl From<&TimeEntryBreak> for Break {
+    fn from(value: &TimeEntryBreak) -> Self {
+        Self { start: value.start_time, end: value.end_time }
+    }
+}
+
+/// Prints `text` in text mode (nothing when it is empty), otherwise the serialized `data`
+pub fn print<T: Serialize, F: FnOnce() -> String>(data: &T, text: F) {
+    match format() {
+        Format::Text => {
+            let text = text();
+            if !text.trim_end().is_empty() {
+                println!("{}", text.trim_end());
+            }
+        }
+        Format::Json => println!("{}", serde_json::to_string_pretty(data).unwrap()),
+        Format::Yaml => print!("{}", serde_norway::to_string(data).unwrap()),
+    }
+}
+
+/// Prints a note for the user, to stderr when stdout carries serialized results
+pub fn note(text: &str) {
+    match format() {
+        Format::Text => println!("{text}"),
+        Format::Json | Format::Yaml => eprintln!("{text}"),
+    }
+}
+
+/// Prints a `Report` about `entry`, or `text` in text mode
+pub fn report<F: FnOnce() -> String>(event: &'static str, entry: Option<&TimeEntry>, text: F) {
+    let report = Report { event, entry: entry.map(Entry::from) };
+    print(&report, text);
+}
+
+/// Prints a failure, to stderr in text mode, otherwise as a serialized `ErrorReport` on stdout
+pub fn error(message: String) {
+    match format() {
+        Format::Text => eprintln!("{message}"),
+        Format::Json | Format::Yaml => print(&ErrorReport { event: "error", error: message }, String::new),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rippling_api::time_entries::TimeEntry;
+    use serde_json::json;
+    use utilities::mocking;
+
+    use super::*;
+
+    #[test]
+    fn it_serializes_a_stable_report() {
+        let entry: TimeEntry = serde_json::from_str(&mocking::read_fixture("entry_closed")).unwrap();
+        let report = Report { event: "status", entry: Some(Entry::from(&entry)) };
+        assert_eq!(
+            serde_json::to_value(&report).unwrap(),
+            json!({
+                "event": "status",
+                "entry": {
+                    "id": "some-entry-id",
+                    "start": "2023-01-19T09:22:25+01:00",
+                    "end": "2023-01-19T17:22:25+01:00",
+                    "breaks": [{"start": "2023-01-19T13:00:00+01:00", "end": "2023-01-19T13:30:00+01:00"}],
+                    "on_break": false,
+                    "regular_hours": 7.5,
+                    "unpaid_break_hours": 0.5
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn it_serializes_an_error_report() {
+        let report = ErrorReport { event: "error", error: "Not clocked in".into() };
+        assert_eq!(
+            serde_json::to_value(&report).unwrap(),
+            json!({"event": "error", "error": "Not clocked in"})
+        );
+    }
+}
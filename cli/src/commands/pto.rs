@@ -1,11 +1,112 @@
+mod plan;
+mod report;
+
 use std::thread;
 
-use rippling_api::{pto::Holiday, Client};
-use time::Date;
+use clap::{Args, Subcommand, ValueEnum};
+use rippling_api::{
+    pto::{AccrualUnit, Holiday, HolidaysOfYear, LeaveRequest, NewLeaveRequest},
+    Client,
+};
+use time::{macros::format_description, Date};
+
+use crate::{persistence, spinner_wrap};
 
-use crate::persistence;
+use super::{output, DateRange, Error, Result};
 
-use super::Result;
+const DATE_FMT: &[time::format_description::FormatItem] =
+    format_description!("[weekday repr:short] [day] [month repr:short] [year]");
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Check if a day is a working day, a weekend, a holiday or PTO
+    Check {
+        /// Defaults to today
+        #[arg(value_parser = super::parse_date)]
+        date: Option<Date>,
+    },
+    /// List the holidays of a year
+    Holidays {
+        /// Defaults to the current year
+        #[arg(short, long)]
+        year: Option<i32>,
+    },
+    /// List leave requests
+    Requests {
+        /// Only list requests with this status
+        #[arg(short, long, value_enum, default_value_t = StatusFilter::Approved)]
+        status: StatusFilter,
+    },
+    /// Show the leave balance of each leave policy
+    Balance,
+    /// List the leave types of the company and your leave policies
+    Types,
+    /// Suggest bridge days and short stretches of leave around holidays
+    ///
+    /// A stretch of up to 4 working days between days off is suggested when every day of leave
+    /// gives at least two and a half days off, for example a bridge day before a weekend or two
+    /// days next to a holiday on a Wednesday.
+    Plan {
+        /// Defaults to the current year
+        #[arg(short, long)]
+        year: Option<i32>,
+    },
+    /// Cancel an upcoming leave request
+    Cancel {
+        /// Bypass prompt with a yes answer
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Request leave
+    Request(RequestArgs),
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum StatusFilter {
+    Approved,
+    Pending,
+    Rejected,
+    All,
+}
+
+impl StatusFilter {
+    fn as_param(&self) -> Option<&'static str> {
+        match self {
+            Self::Approved => Some("APPROVED"),
+            Self::Pending => Some("PENDING"),
+            Self::Rejected => Some("REJECTED"),
+            Self::All => None,
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct RequestArgs {
+    /// Leave type, for example Vacation
+    #[arg(short = 't', long = "type")]
+    pub leave_type: String,
+    /// Single day or range, for example 2026-12-22..2026-12-31
+    #[arg(value_parser = super::parse_date_range)]
+    pub dates: DateRange,
+    /// Only take the first half of the first day
+    #[arg(long, conflicts_with = "start_hours")]
+    pub half_day_start: bool,
+    /// Only take the first half of the last day
+    #[arg(long, conflicts_with = "end_hours")]
+    pub half_day_end: bool,
+    /// Hours taken on the first day
+    #[arg(long)]
+    pub start_hours: Option<f32>,
+    /// Hours taken on the last day
+    #[arg(long)]
+    pub end_hours: Option<f32>,
+    /// Reason for the leave
+    #[arg(short, long)]
+    pub reason: Option<String>,
+    /// Bypass prompt with a yes answer
+    #[arg(short, long)]
+    pub yes: bool,
+}
 
 #[derive(Debug)]
 pub enum CheckOutcome {
@@ -15,42 +116,283 @@ pub enum CheckOutcome {
     Weekend(time::Weekday),
 }
 
-pub fn check(date: Date) -> Result<CheckOutcome> {
-    let tw = thread::spawn(move || is_weekend(date));
-    let th = thread::spawn(move || check_holiday(date));
-    let tl = thread::spawn(move || is_leave_request(date));
+impl std::fmt::Display for CheckOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Leave => write!(f, "You are on PTO"),
+            Self::Holiday(h) => write!(f, "It is a holiday ({})", h.name),
+            Self::WorkingDay => write!(f, "It is a working day"),
+            Self::Weekend(d) => write!(f, "It is a weekend ({d})"),
+        }
+    }
+}
 
-    if let Some(weekend) = tw.join().unwrap() {
-        Ok(CheckOutcome::Weekend(weekend))
-    } else if let Some(holiday) = th.join().unwrap()? {
-        Ok(CheckOutcome::Holiday(holiday))
-    } else if tl.join().unwrap()? {
-        Ok(CheckOutcome::Leave)
-    } else {
-        Ok(CheckOutcome::WorkingDay)
+/// Entrypoint for this module
+pub fn execute(cmd: &Command) -> Result<()> {
+    match cmd {
+        Command::Check { date } => {
+            let client: Client = persistence::state().into();
+            let date = date.unwrap_or_else(super::today);
+            let outcome = spinner_wrap!(check(&client, date))?;
+            output::print(&report::CheckReport::new(date, &outcome), || outcome.to_string());
+        }
+        Command::Holidays { year } => {
+            let year = year.unwrap_or_else(|| super::today().year());
+            let client: Client = persistence::state().into();
+            let calendar = spinner_wrap!(client.holiday_calendar())?;
+            let holidays = calendar
+                .into_iter()
+                .find(|hy| i32::from(hy.year) == year)
+                .map(|hy| hy.holidays)
+                .unwrap_or_default();
+            let report = report::HolidaysReport {
+                event: "pto_holidays",
+                year,
+                holidays: holidays.iter().map(report::HolidayItem::from).collect(),
+            };
+            output::print(&report, || {
+                holidays
+                    .iter()
+                    .map(|h| format!("{}  {}\n", format_range(h.start_date, h.end_date), h.name))
+                    .collect()
+            });
+        }
+        Command::Requests { status } => {
+            let client: Client = persistence::state().into();
+            let mut requests = spinner_wrap!(client.leave_requests(status.as_param()))?;
+            requests.sort_by_key(|r| r.start_date);
+            let report = report::RequestsReport {
+                event: "pto_requests",
+                requests: requests.iter().map(report::Leave::from).collect(),
+            };
+            output::print(&report, || requests.iter().map(|r| format!("{r}\n")).collect());
+        }
+        Command::Cancel { yes } => cancel_leave(*yes)?,
+        Command::Balance => balance()?,
+        Command::Types => types()?,
+        Command::Plan { year } => plan(year.unwrap_or_else(|| super::today().year()))?,
+        Command::Request(args) => request_leave(args)?,
+    }
+    Ok(())
+}
+
+fn request_leave(args: &RequestArgs) -> Result<()> {
+    let client: Client = persistence::state().into();
+    let policy_id = spinner_wrap!(leave_policy_id(&client, &args.leave_type))?;
+    let mut request = NewLeaveRequest::new(policy_id, args.dates.from, args.dates.to);
+    request.start_date_half_day = args.half_day_start;
+    request.end_date_half_day = args.half_day_end;
+    request.start_date_custom_hours = args.start_hours;
+    request.end_date_custom_hours = args.end_hours;
+    request.reason = args.reason.clone();
+
+    let mut preview = format!("{} {}", args.leave_type, format_range(args.dates.from, args.dates.to));
+    if args.half_day_start || args.start_hours.is_some() {
+        preview.push_str(" (partial first day)");
     }
+    if args.half_day_end || args.end_hours.is_some() {
+        preview.push_str(" (partial last day)");
+    }
+    let created = if args.yes || super::confirm(&format!("Request {preview}?"))? {
+        Some(spinner_wrap!(client.create_leave_request(&request))?)
+    } else {
+        None
+    };
+    let report = report::LeaveReport { event: "pto_request", request: created.as_ref().map(report::Leave::from) };
+    output::print(&report, || match &created {
+        Some(created) => format!("Requested {preview}, status is {}", created.status),
+        None => String::new(),
+    });
+    Ok(())
+}
+
+fn balance() -> Result<()> {
+    let client: Client = persistence::state().into();
+    let balances = spinner_wrap!(client.leave_balances())?;
+    let report = report::BalanceReport {
+        event: "pto_balance",
+        balances: balances.iter().map(report::Balance::from).collect(),
+    };
+    output::print(&report, || {
+        let mut text = format!(
+            "{:<30} {:>8} {:>8} {:>8} {:>9}\n",
+            "Policy", "Accrued", "Used", "Pending", "Available"
+        );
+        for balance in &balances {
+            let amount = |value: f32| match balance.unit() {
+                AccrualUnit::Days => format!("{value:.1}d"),
+                AccrualUnit::Hours => format!("{value:.1}h"),
+            };
+            let available = if balance.unlimited {
+                "unlimited".into()
+            } else {
+                amount(balance.available)
+            };
+            text.push_str(&format!(
+                "{:<30} {:>8} {:>8} {:>8} {:>9}\n",
+                balance.policy_name,
+                amount(balance.accrued),
+                amount(balance.used),
+                amount(balance.pending),
+                available
+            ));
+        }
+        text
+    });
+    Ok(())
 }
 
-fn check_holiday(date: Date) -> Result<Option<Holiday>> {
+fn cancel_leave(yes: bool) -> Result<()> {
     let client: Client = persistence::state().into();
-    let cal = client.holiday_calendar()?;
-    match cal.into_iter().find(|hy| i32::from(hy.year) == date.year()) {
-        Some(year) => Ok(year
-            .holidays
-            .into_iter()
-            .find(|h| h.start_date <= date && h.end_date >= date)),
-        None => Ok(None),
+    let today = super::today();
+    let mut upcoming: Vec<LeaveRequest> = spinner_wrap!(client.leave_requests(None))?
+        .into_iter()
+        .filter(|r| r.end_date >= today && (r.status == "PENDING" || r.status == "APPROVED"))
+        .collect();
+    upcoming.sort_by_key(|r| r.start_date);
+    if upcoming.is_empty() {
+        let report = report::LeaveReport { event: "pto_cancel", request: None };
+        output::print(&report, || "No upcoming leave requests".into());
+        return Ok(());
     }
+    let request = super::select("Which leave request?", upcoming)?;
+    let cancelled = if yes || super::confirm(&format!("Cancel {request}?"))? {
+        Some(spinner_wrap!(client.cancel_leave_request(&request.id))?)
+    } else {
+        None
+    };
+    let report = report::LeaveReport { event: "pto_cancel", request: cancelled.as_ref().map(report::Leave::from) };
+    output::print(&report, || match &cancelled {
+        Some(cancelled) => format!("Cancelled {cancelled}"),
+        None => String::new(),
+    });
+    Ok(())
 }
 
-fn is_leave_request(date: Date) -> Result<bool> {
+fn plan(year: i32) -> Result<()> {
     let client: Client = persistence::state().into();
-    let lr = client.leave_requests()?;
-    let found = lr.into_iter().any(|r| r.start_date <= date && r.end_date >= date);
-    Ok(found)
+    let (calendar, requests) = spinner_wrap!((client.holiday_calendar()?, client.leave_requests(Some("APPROVED"))?));
+    let holidays: Vec<Holiday> = calendar
+        .into_iter()
+        .filter(|hy| (year - 1..=year + 1).contains(&i32::from(hy.year)))
+        .flat_map(|hy| hy.holidays)
+        .collect();
+    let leave: Vec<(Date, Date)> = requests.iter().map(|r| (r.start_date, r.end_date)).collect();
+    let report =
+        report::PlanReport { event: "pto_plan", year, suggestions: plan::suggestions(year, &holidays, &leave) };
+    output::print(&report, || {
+        report
+            .suggestions
+            .iter()
+            .map(|suggestion| {
+                format!(
+                    "{:<33} {} day(s) of leave for {} days off ({})\n",
+                    format_range(suggestion.from, suggestion.to),
+                    suggestion.cost,
+                    suggestion.days_off,
+                    suggestion.holidays.join(", ")
+                )
+            })
+            .collect()
+    });
+    Ok(())
 }
 
-fn is_weekend(date: Date) -> Option<time::Weekday> {
+fn types() -> Result<()> {
+    let client: Client = persistence::state().into();
+    let (types, policies) = spinner_wrap!((client.leave_types()?, client.leave_policies()?));
+    let types = types
+        .iter()
+        .filter(|t| !t.deleted)
+        .map(|t| report::LeaveTypeItem::new(t, policies.iter().find(|p| !p.deleted && p.leave_type_id == t.id)))
+        .collect();
+    let report = report::TypesReport { event: "pto_types", types };
+    output::print(&report, || {
+        let mut text = format!("{:<24} {:<5} {:<6} Policy\n", "Type", "Paid", "Unit");
+        for leave_type in &report.types {
+            text.push_str(&format!(
+                "{:<24} {:<5} {:<6} {}\n",
+                leave_type.name,
+                if leave_type.paid { "yes" } else { "no" },
+                leave_type.unit.unwrap_or("-"),
+                leave_type.policy.as_deref().unwrap_or("-")
+            ));
+        }
+        text
+    });
+    Ok(())
+}
+
+/// Looks up your leave policy for a leave type by the name of the type
+fn leave_policy_id(client: &Client, leave_type: &str) -> Result<String> {
+    let unknown = || Error::UnknownLeaveType(leave_type.to_owned());
+    let found = client
+        .leave_types()?
+        .into_iter()
+        .find(|t| !t.deleted && t.name.eq_ignore_ascii_case(leave_type))
+        .ok_or_else(unknown)?;
+    client
+        .leave_policies()?
+        .into_iter()
+        .find(|p| !p.deleted && p.leave_type_id == found.id)
+        .map(|p| p.id)
+        .ok_or_else(unknown)
+}
+
+fn format_range(start: Date, end: Date) -> String {
+    if start == end {
+        start.format(DATE_FMT).unwrap()
+    } else {
+        format!(
+            "{} - {}",
+            start.format(DATE_FMT).unwrap(),
+            end.format(DATE_FMT).unwrap()
+        )
+    }
+}
+
+pub fn check(client: &Client, date: Date) -> Result<CheckOutcome> {
+    Ok(DaysOff::fetch(client)?.check(date))
+}
+
+/// Holidays and approved leave, fetched once to check any number of days
+pub struct DaysOff {
+    holidays: Vec<HolidaysOfYear>,
+    leave: Vec<LeaveRequest>,
+}
+
+impl DaysOff {
+    pub fn fetch(client: &Client) -> Result<Self> {
+        let th = {
+            let client = client.clone();
+            thread::spawn(move || client.holiday_calendar())
+        };
+        let leave = client.leave_requests(Some("APPROVED"))?;
+        Ok(Self { holidays: th.join().unwrap()?, leave })
+    }
+
+    pub fn check(&self, date: Date) -> CheckOutcome {
+        if let Some(weekend) = is_weekend(date) {
+            CheckOutcome::Weekend(weekend)
+        } else if let Some(holiday) = self.holiday(date) {
+            CheckOutcome::Holiday(holiday.clone())
+        } else if self.leave.iter().any(|r| r.start_date <= date && r.end_date >= date) {
+            CheckOutcome::Leave
+        } else {
+            CheckOutcome::WorkingDay
+        }
+    }
+
+    fn holiday(&self, date: Date) -> Option<&Holiday> {
+        self.holidays
+            .iter()
+            .filter(|hy| i32::from(hy.year) == date.year())
+            .flat_map(|hy| &hy.holidays)
+            .find(|h| h.start_date <= date && h.end_date >= date)
+    }
+}
+
+pub(super) fn is_weekend(date: Date) -> Option<time::Weekday> {
     let day = date.weekday();
     match day {
         time::Weekday::Monday => None,
@@ -61,3 +403,31 @@ fn is_weekend(date: Date) -> Option<time::Weekday> {
         _ => Some(day),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use time::macros::date;
+    use utilities::mocking;
+
+    use super::*;
+
+    #[test]
+    fn days_off_checks_many_days() {
+        let days_off = DaysOff {
+            holidays: serde_json::from_str(&mocking::read_fixture("holiday_calendar")).unwrap(),
+            leave: serde_json::from_str(&mocking::read_fixture("leave_requests")).unwrap(),
+        };
+        assert!(
+            matches!(days_off.check(date!(2016 - 01 - 01)), CheckOutcome::Holiday(h) if h.name == "New Year's Day")
+        );
+        assert!(matches!(days_off.check(date!(2022 - 06 - 10)), CheckOutcome::Leave));
+        assert!(matches!(
+            days_off.check(date!(2022 - 06 - 11)),
+            CheckOutcome::Weekend(_)
+        ));
+        assert!(matches!(
+            days_off.check(date!(2022 - 06 - 13)),
+            CheckOutcome::WorkingDay
+        ));
+    }
+}
@@ -0,0 +1,165 @@
+use rippling_api::pto::Holiday;
+use serde::Serialize;
+use time::{Date, Duration, Month};
+
+/// Longest stretch of working days which is still suggested as leave
+const MAX_LEAVE_DAYS: usize = 4;
+
+/// A stretch of working days which connects holidays and weekends into a longer time off
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct Suggestion {
+    pub from: Date,
+    pub to: Date,
+    /// Leave days needed
+    #[serde(rename = "leave_days")]
+    pub cost: usize,
+    /// Length of the continuous time off when taking the leave
+    pub days_off: usize,
+    pub holidays: Vec<String>,
+}
+
+/// Consecutive days which are either all off or all working days
+struct Run {
+    off: bool,
+    from: Date,
+    to: Date,
+    len: usize,
+    holidays: Vec<String>,
+}
+
+/// Finds bridge days and short stretches of leave starting in `year`,
+/// skipping days which are already `leave` (inclusive date ranges)
+pub fn suggestions(year: i32, holidays: &[Holiday], leave: &[(Date, Date)]) -> Vec<Suggestion> {
+    let runs = runs(year, holidays, leave);
+    runs.windows(3)
+        .filter(|w| w[0].off && !w[1].off && w[2].off)
+        .filter(|w| w[1].len <= MAX_LEAVE_DAYS && w[1].from.year() == year)
+        .filter(|w| !w[0].holidays.is_empty() || !w[2].holidays.is_empty())
+        // Every leave day has to give at least two and a half days off, so one day bridging into a
+        // long weekend or two days next to a holiday in the middle of the week are worth it
+        .filter(|w| 2 * (w[0].len + w[1].len + w[2].len) >= 5 * w[1].len)
+        .map(|w| Suggestion {
+            from: w[1].from,
+            to: w[1].to,
+            cost: w[1].len,
+            days_off: w[0].len + w[1].len + w[2].len,
+            holidays: w[0].holidays.iter().chain(w[2].holidays.iter()).cloned().collect(),
+        })
+        .collect()
+}
+
+fn runs(year: i32, holidays: &[Holiday], leave: &[(Date, Date)]) -> Vec<Run> {
+    // Look beyond the year, so that runs at its edges are complete
+    let mut date = Date::from_calendar_date(year, Month::January, 1).unwrap() - Duration::days(14);
+    let last = Date::from_calendar_date(year, Month::December, 31).unwrap() + Duration::days(14);
+
+    let mut runs: Vec<Run> = Vec::new();
+    while date <= last {
+        let holiday = holidays.iter().find(|h| h.start_date <= date && h.end_date >= date);
+        let off = holiday.is_some()
+            || super::is_weekend(date).is_some()
+            || leave.iter().any(|(from, to)| *from <= date && *to >= date);
+        match runs.last_mut() {
+            Some(run) if run.off == off => {
+                run.to = date;
+                run.len += 1;
+            }
+            _ => runs.push(Run { off, from: date, to: date, len: 1, holidays: Vec::new() }),
+        }
+        if let Some(holiday) = holiday {
+            let run = runs.last_mut().unwrap();
+            if !run.holidays.contains(&holiday.name) {
+                run.holidays.push(holiday.name.clone());
+            }
+        }
+        date = date.next_day().unwrap();
+    }
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::date;
+
+    use super::*;
+
+    fn holiday(name: &str, date: Date) -> Holiday {
+        Holiday {
+            name: name.into(),
+            kind: "FULL_DAY".into(),
+            start_date: date,
+            end_date: date,
+            count_as_overtime: false,
+        }
+    }
+
+    fn holidays() -> Vec<Holiday> {
+        vec![
+            holiday("Ascension Day", date!(2026 - 05 - 14)),
+            holiday("Christmas Day", date!(2026 - 12 - 25)),
+            holiday("Boxing Day", date!(2026 - 12 - 26)),
+            holiday("New Year's Day", date!(2027 - 01 - 01)),
+        ]
+    }
+
+    #[test]
+    fn it_suggests_bridge_days_and_stretches() {
+        let plan = suggestions(2026, &holidays(), &[]);
+        assert_eq!(
+            plan,
+            vec![
+                Suggestion {
+                    from: date!(2026 - 05 - 15),
+                    to: date!(2026 - 05 - 15),
+                    cost: 1,
+                    days_off: 4,
+                    holidays: vec!["Ascension Day".into()],
+                },
+                Suggestion {
+                    from: date!(2026 - 12 - 28),
+                    to: date!(2026 - 12 - 31),
+                    cost: 4,
+                    days_off: 10,
+                    holidays: vec!["Christmas Day".into(), "Boxing Day".into(), "New Year's Day".into()],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn it_suggests_both_halves_of_a_week_around_a_holiday_on_wednesday() {
+        let wednesday = date!(2026 - 10 - 14);
+        assert_eq!(wednesday.weekday(), time::Weekday::Wednesday);
+        let plan = suggestions(2026, &[holiday("Some Holiday", wednesday)], &[]);
+        assert_eq!(
+            plan,
+            vec![
+                Suggestion {
+                    from: date!(2026 - 10 - 12),
+                    to: date!(2026 - 10 - 13),
+                    cost: 2,
+                    days_off: 5,
+                    holidays: vec!["Some Holiday".into()],
+                },
+                Suggestion {
+                    from: date!(2026 - 10 - 15),
+                    to: date!(2026 - 10 - 16),
+                    cost: 2,
+                    days_off: 5,
+                    holidays: vec!["Some Holiday".into()],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn it_skips_days_already_on_leave() {
+        let leave = [(date!(2026 - 05 - 15), date!(2026 - 05 - 15))];
+        let plan = suggestions(2026, &holidays(), &leave);
+        assert_eq!(plan.len(), 2);
+        // The rest of the week before is now worth it
+        let first = plan.first().unwrap();
+        assert_eq!((first.from, first.to), (date!(2026 - 05 - 11), date!(2026 - 05 - 13)));
+        assert_eq!(first.days_off, 9);
+    }
+}
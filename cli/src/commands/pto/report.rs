@@ -0,0 +1,343 @@
+use rippling_api::pto::{AccrualUnit, Holiday, LeaveBalance, LeavePolicy, LeaveRequest, LeaveType};
+use serde::Serialize;
+use time::Date;
+
+use super::{plan::Suggestion, CheckOutcome};
+
+/// Result of `pto check`, as printed with `--output json|yaml`
+#[derive(Debug, Serialize)]
+pub struct CheckReport {
+    pub event: &'static str,
+    pub date: Date,
+    /// One of `working_day`, `weekend`, `holiday` or `leave`
+    pub day: &'static str,
+    /// Name of the holiday, `null` on any other day
+    pub holiday: Option<String>,
+}
+
+impl CheckReport {
+    pub fn new(date: Date, outcome: &CheckOutcome) -> Self {
+        let (day, holiday) = match outcome {
+            CheckOutcome::Leave => ("leave", None),
+            CheckOutcome::Holiday(h) => ("holiday", Some(h.name.clone())),
+            CheckOutcome::WorkingDay => ("working_day", None),
+            CheckOutcome::Weekend(_) => ("weekend", None),
+        };
+        Self { event: "pto_check", date, day, holiday }
+    }
+}
+
+/// Result of `pto holidays`, as printed with `--output json|yaml`
+#[derive(Debug, Serialize)]
+pub struct HolidaysReport {
+    pub event: &'static str,
+    pub year: i32,
+    pub holidays: Vec<HolidayItem>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HolidayItem {
+    pub name: String,
+    pub start: Date,
+    pub end: Date,
+}
+
+impl From<&Holiday> for HolidayItem {
+    fn from(value: &Holiday) -> Self {
+        Self { name: value.name.clone(), start: value.start_date, end: value.end_date }
+    }
+}
+
+/// Result of `pto requests`, as printed with `--output json|yaml`
+#[derive(Debug, Serialize)]
+pub struct RequestsReport {
+    pub event: &'static str,
+    pub requests: Vec<Leave>,
+}
+
+/// Result of a command acting on a single leave request, as printed with `--output json|yaml`
+#[derive(Debug, Serialize)]
+pub struct LeaveReport {
+    pub event: &'static str,
+    /// The affected request, `null` when nothing was done
+    pub request: Option<Leave>,
+}
+
+/// Stable representation of a `LeaveRequest`
+#[derive(Debug, Serialize)]
+pub struct Leave {
+    pub id: String,
+    pub leave_type: String,
+    pub start: Date,
+    pub end: Date,
+    pub start_half_day: bool,
+    pub end_half_day: bool,
+    pub status: String,
+    pub reason: Option<String>,
+}
+
+impl From<&LeaveRequest> for Leave {
+    fn from(value: &LeaveRequest) -> Self {
+        Self {
+            id: value.id.clone(),
+            leave_type: value.leave_type_name.clone(),
+            start: value.start_date,
+            end: value.end_date,
+            start_half_day: value.start_date_half_day,
+            end_half_day: value.end_date_half_day,
+            status: value.status.clone(),
+            reason: value.reason.clone(),
+        }
+    }
+}
+
+/// Result of `pto balance`, as printed with `--output json|yaml`
+#[derive(Debug, Serialize)]
+pub struct BalanceReport {
+    pub event: &'static str,
+    pub balances: Vec<Balance>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Balance {
+    pub policy: String,
+    pub leave_type: String,
+    /// Either `days` or `hours`, all amounts are given in it
+    pub unit: &'static str,
+    pub accrued: f32,
+    pub used: f32,
+    pub pending: f32,
+    /// `null` for unlimited policies
+    pub available: Option<f32>,
+}
+
+impl From<&LeaveBalance> for Balance {
+    fn from(value: &LeaveBalance) -> Self {
+        Self {
+            policy: value.policy_name.clone(),
+            leave_type: value.leave_type_name.clone(),
+            unit: unit_name(value.unit()),
+            accrued: value.accrued,
+            used: value.used,
+            pending: value.pending,
+            available: (!value.unlimited).then_some(value.available),
+        }
+    }
+}
+
+/// Result of `pto types`, as printed with `--output json|yaml`
+#[derive(Debug, Serialize)]
+pub struct TypesReport {
+    pub event: &'static str,
+    pub types: Vec<LeaveTypeItem>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LeaveTypeItem {
+    pub name: String,
+    pub paid: bool,
+    /// Your policy for this type, `null` when you have none
+    pub policy: Option<String>,
+    /// Either `days` or `hours`, `null` without a policy
+    pub unit: Option<&'static str>,
+}
+
+impl LeaveTypeItem {
+    pub fn new(leave_type: &LeaveType, policy: Option<&LeavePolicy>) -> Self {
+        Self {
+            name: leave_type.name.clone(),
+            paid: leave_type.paid,
+            policy: policy.map(|p| p.name.clone()),
+            unit: policy.map(|p| unit_name(p.unit())),
+        }
+    }
+}
+
+/// Result of `pto plan`, as printed with `--output json|yaml`
+#[derive(Debug, Serialize)]
+pub struct PlanReport {
+    pub event: &'static str,
+    pub year: i32,
+    pub suggestions: Vec<Suggestion>,
+}
+
+fn unit_name(unit: AccrualUnit) -> &'static str {
+    match unit {
+        AccrualUnit::Days => "days",
+        AccrualUnit::Hours => "hours",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use time::macros::date;
+    use utilities::mocking;
+
+    use super::*;
+
+    #[test]
+    fn it_serializes_a_check_report() {
+        let holiday = Holiday {
+            name: "New Year's Day".into(),
+            kind: "PUBLIC".into(),
+            start_date: date!(2026 - 01 - 01),
+            end_date: date!(2026 - 01 - 01),
+            count_as_overtime: false,
+        };
+        let report = CheckReport::new(date!(2026 - 01 - 01), &CheckOutcome::Holiday(holiday));
+        assert_eq!(
+            serde_json::to_value(&report).unwrap(),
+            json!({"event": "pto_check", "date": "2026-01-01", "day": "holiday", "holiday": "New Year's Day"})
+        );
+        let report = CheckReport::new(date!(2026 - 01 - 02), &CheckOutcome::WorkingDay);
+        assert_eq!(
+            serde_json::to_value(&report).unwrap(),
+            json!({"event": "pto_check", "date": "2026-01-02", "day": "working_day", "holiday": null})
+        );
+    }
+
+    #[test]
+    fn it_serializes_a_holidays_report() {
+        let calendar: Vec<rippling_api::pto::HolidaysOfYear> =
+            serde_json::from_str(&mocking::read_fixture("holiday_calendar")).unwrap();
+        let report = HolidaysReport {
+            event: "pto_holidays",
+            year: 2016,
+            holidays: calendar[0].holidays.iter().take(1).map(HolidayItem::from).collect(),
+        };
+        assert_eq!(
+            serde_json::to_value(&report).unwrap(),
+            json!({
+                "event": "pto_holidays",
+                "year": 2016,
+                "holidays": [{"name": "New Year's Day", "start": "2016-01-01", "end": "2016-01-01"}]
+            })
+        );
+    }
+
+    #[test]
+    fn it_serializes_a_requests_report() {
+        let requests: Vec<LeaveRequest> = serde_json::from_str(&mocking::read_fixture("leave_requests")).unwrap();
+        let report = RequestsReport {
+            event: "pto_requests",
+            requests: requests.iter().take(1).map(Leave::from).collect(),
+        };
+        assert_eq!(
+            serde_json::to_value(&report).unwrap(),
+            json!({
+                "event": "pto_requests",
+                "requests": [{
+                    "id": "pto-request-1",
+                    "leave_type": "Vacation",
+                    "start": "2022-06-09",
+                    "end": "2022-06-10",
+                    "start_half_day": false,
+                    "end_half_day": false,
+                    "status": "APPROVED",
+                    "reason": null
+                }]
+            })
+        );
+    }
+
+    #[test]
+    fn it_serializes_a_leave_report() {
+        let request: LeaveRequest = serde_json::from_str(&mocking::read_fixture("leave_request")).unwrap();
+        let report = LeaveReport { event: "pto_request", request: Some(Leave::from(&request)) };
+        let value = serde_json::to_value(&report).unwrap();
+        assert_eq!(value["event"], "pto_request");
+        assert_eq!(value["request"]["id"], request.id.as_str());
+        assert_eq!(value["request"]["status"], request.status.as_str());
+    }
+
+    #[test]
+    fn it_serializes_an_empty_leave_report() {
+        let report = LeaveReport { event: "pto_cancel", request: None };
+        assert_eq!(
+            serde_json::to_value(&report).unwrap(),
+            json!({"event": "pto_cancel", "request": null})
+        );
+    }
+
+    #[test]
+    fn it_serializes_a_balance_report() {
+        let balances: Vec<LeaveBalance> = serde_json::from_str(&mocking::read_fixture("leave_balances")).unwrap();
+        let report = BalanceReport { event: "pto_balance", balances: balances.iter().map(Balance::from).collect() };
+        assert_eq!(
+            serde_json::to_value(&report).unwrap(),
+            json!({
+                "event": "pto_balance",
+                "balances": [
+                    {
+                        "policy": "Germany Annual Leave 28 Days",
+                        "leave_type": "Vacation",
+                        "unit": "days",
+                        "accrued": 28.0,
+                        "used": 12.5,
+                        "pending": 6.5,
+                        "available": 9.0
+                    },
+                    {
+                        "policy": "Sick Leave Germany",
+                        "leave_type": "Sick Leave Germany",
+                        "unit": "hours",
+                        "accrued": 0.0,
+                        "used": 8.0,
+                        "pending": 0.0,
+                        "available": null
+                    }
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn it_serializes_a_types_report() {
+        let types: Vec<LeaveType> = serde_json::from_str(&mocking::read_fixture("leave_types")).unwrap();
+        let policies: Vec<LeavePolicy> = serde_json::from_str(&mocking::read_fixture("leave_policies")).unwrap();
+        let report = TypesReport {
+            event: "pto_types",
+            types: vec![
+                LeaveTypeItem::new(&types[0], Some(&policies[0])),
+                LeaveTypeItem::new(&types[2], None),
+            ],
+        };
+        assert_eq!(
+            serde_json::to_value(&report).unwrap(),
+            json!({
+                "event": "pto_types",
+                "types": [
+                    {"name": "Vacation", "paid": true, "policy": "Germany Annual Leave 28 Days", "unit": "days"},
+                    {"name": "Unpaid Leave", "paid": false, "policy": null, "unit": null}
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn it_serializes_a_plan_report() {
+        let suggestion = Suggestion {
+            from: date!(2026 - 05 - 15),
+            to: date!(2026 - 05 - 15),
+            cost: 1,
+            days_off: 4,
+            holidays: vec!["Ascension Day".into()],
+        };
+        let report = PlanReport { event: "pto_plan", year: 2026, suggestions: vec![suggestion] };
+        assert_eq!(
+            serde_json::to_value(&report).unwrap(),
+            json!({
+                "event": "pto_plan",
+                "year": 2026,
+                "suggestions": [{
+                    "from": "2026-05-15",
+                    "to": "2026-05-15",
+                    "leave_days": 1,
+                    "days_off": 4,
+                    "holidays": ["Ascension Day"]
+                }]
+            })
+        );
+    }
+}
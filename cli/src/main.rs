@@ -4,26 +4,93 @@ pub mod persistence;
 use std::{
     fs::{self, File},
     io::IsTerminal,
+    process::ExitCode,
     sync::OnceLock,
 };
 
 use clap::Parser;
-use commands::Commands;
+use commands::{output, Commands};
 use directories::ProjectDirs;
 
 static INTERACTIVE: OnceLock<bool> = OnceLock::new();
 
+/// Exit code when the access token is invalid or expired (EX_NOPERM)
+const EXIT_UNAUTHORIZED: u8 = 77;
+
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+
+    /// Format of the printed results
+    #[arg(short, long, global = true, value_enum, default_value_t)]
+    output: output::Format,
 }
 
-fn main() -> commands::Result<()> {
+fn main() -> ExitCode {
     init_logging();
     let cli = Cli::parse();
-    commands::execute(&cli.command)
+    output::init(cli.output);
+    match commands::execute(&cli.command) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) if e.is_unauthorized() && can_prompt(&cli) => reauthenticate(&cli),
+        Err(e) => fail(e),
+    }
+}
+
+fn can_prompt(cli: &Cli) -> bool {
+    is_interactive() && std::io::stdin().is_terminal() && !matches!(cli.command, Commands::Configure { .. })
+}
+
+/// Asks for a new access token and runs the command once more when it only reads
+///
+/// A command which changes something might have done part of it before the token was rejected,
+/// so it is left to the user to run it again.
+fn reauthenticate(cli: &Cli) -> ExitCode {
+    let retry = cli.command.is_read_only();
+    let prompt = if retry {
+        "Your access token expired, paste a new one to retry (empty to abort):"
+    } else {
+        "Your access token expired, paste a new one to store it (empty to abort):"
+    };
+    let token = inquire::Password::new(prompt)
+        .without_confirmation()
+        .prompt()
+        .unwrap_or_default();
+    if token.trim().is_empty() {
+        return fail(commands::Error::ApiError(rippling_api::Error::Unauthorized));
+    }
+    if let Err(e) = commands::set_access_token(token.trim()) {
+        return fail(e);
+    }
+    if !retry {
+        output::error("Stored the new access token, check what was done and run the command again".into());
+        return ExitCode::FAILURE;
+    }
+    match commands::execute(&cli.command) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => fail(e),
+    }
+}
+
+fn fail(error: commands::Error) -> ExitCode {
+    if let commands::Error::Aborted = error {
+        output::error("Aborted".into());
+        ExitCode::FAILURE
+    } else if error.is_unauthorized() {
+        output::error(
+            "Your access token expired or is invalid, run `rippling-cli configure access-token <token>` with a new one"
+                .into(),
+        );
+        ExitCode::from(EXIT_UNAUTHORIZED)
+    } else {
+        match output::format() {
+            output::Format::Text => output::error(format!("Error: {error:?}")),
+            output::Format::Json | output::Format::Yaml => output::error(error.to_string()),
+        }
+        ExitCode::FAILURE
+    }
 }
 
 fn is_interactive() -> bool {
@@ -1,12 +1,16 @@
-use std::sync::OnceLock;
+use std::{fs, path::PathBuf, sync::Mutex};
+
+use rippling_api::ClientBuilder;
 
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use time::OffsetDateTime;
 
 const APP_NAME: &str = "rippling-cli";
-static STATE: OnceLock<State> = OnceLock::new();
+static STATE: Mutex<Option<State>> = Mutex::new(None);
 
-pub fn state() -> &'static State {
-    STATE.get_or_init(State::load)
+/// The stored state, loaded on first use and replaced by `State::store`
+pub fn state() -> State {
+    STATE.lock().unwrap().get_or_insert_with(State::load).clone()
 }
 
 fn load<T: Serialize + DeserializeOwned + Default>(name: &str) -> T {
@@ -22,6 +26,11 @@ pub struct State {
     pub company_id: Option<String>,
     pub role_id: Option<String>,
     pub token: Option<String>,
+    /// Read from the token when it is a JWT
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub token_expires_at: Option<OffsetDateTime>,
+    /// PEM file with additional trusted certificates, for example of a corporate proxy
+    pub ca_certificates: Option<PathBuf>,
 }
 
 impl State {
@@ -33,12 +42,27 @@ impl State {
 
     pub fn store(&self) {
         store(Self::CONFIG_NAME, self);
+        *STATE.lock().unwrap() = Some(self.clone());
+    }
+
+    /// Connection settings shared by all clients
+    pub fn client_builder(&self) -> ClientBuilder {
+        let builder = ClientBuilder::new().user_agent(concat!("rippling-cli/", env!("CARGO_PKG_VERSION")));
+        match &self.ca_certificates {
+            Some(path) => {
+                let pem = fs::read(path).unwrap_or_else(|_| panic!("Could not read {}", path.display()));
+                builder
+                    .add_ca_certificates(&pem)
+                    .unwrap_or_else(|e| panic!("{}: {e}", path.display()))
+            }
+            None => builder,
+        }
     }
 }
 
 impl From<&State> for rippling_api::client::Client {
     fn from(val: &State) -> Self {
-        let client = rippling_api::client::Client::new(val.token.clone().unwrap());
+        let client = val.client_builder().build(val.token.clone().unwrap());
         if let Some(company) = val.company_id.clone() {
             if let Some(role) = val.role_id.clone() {
                 return client.with_company_and_role(company, role);
@@ -47,3 +71,9 @@ impl From<&State> for rippling_api::client::Client {
         client
     }
 }
+
+impl From<State> for rippling_api::client::Client {
+    fn from(val: State) -> Self {
+        Self::from(&val)
+    }
+}
@@ -4,6 +4,12 @@ pub struct FakeRippling {
     server: mockito::ServerGuard,
 }
 
+impl Default for FakeRippling {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl FakeRippling {
     pub fn new() -> Self {
         Self { server: mockito::Server::new() }
@@ -26,8 +32,12 @@ impl FakeRippling {
     }
 
     pub fn with_fixture(&mut self, method: &str, path: &str, fixture: &str) -> Mock {
-        let file = format!("{}/fixtures/{fixture}.json", env!("CARGO_MANIFEST_DIR"));
-        self.default_mock(method, path).with_body_from_file(file)
+        self.default_mock(method, path)
+            .with_body_from_file(fixture_path(fixture))
+    }
+
+    pub fn with_body(&mut self, method: &str, path: &str, body: &str) -> Mock {
+        self.default_mock(method, path).with_body(body)
     }
 
     pub fn mock_active_policy(&mut self) -> Mock {
@@ -48,3 +58,11 @@ impl FakeRippling {
         .create()
     }
 }
+
+fn fixture_path(fixture: &str) -> String {
+    format!("{}/fixtures/{fixture}.json", env!("CARGO_MANIFEST_DIR"))
+}
+
+pub fn read_fixture(fixture: &str) -> String {
+    std::fs::read_to_string(fixture_path(fixture)).unwrap()
+}